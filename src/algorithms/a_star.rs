@@ -1,87 +1,73 @@
 //https://en.wikipedia.org/wiki/A*_search_algorithm
 
-use super::{GridBlock, Point};
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::WeightedHeuristic;
+use super::{reconstruct_path, FrontierNode, Point, SearchResult};
 use crate::algorithms::GridMap;
+use std::collections::BinaryHeap;
 
-pub fn run(grid: &GridMap, start: &Point, end: &Point) {
-    let mut open_set = vec![start];
-    let mut came_from: Vec<&Point> = Vec::new();
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    heuristic: &WeightedHeuristic,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut closed = vec![vec![false; width]; height];
+    let mut gscore = vec![vec![f64::INFINITY; width]; height];
+    let mut came_from: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
 
-    let mut gscore: Vec<(Point, i32)> = Vec::new();
+    // the open set is keyed on fscore, the estimated cost of a path through the node
+    let mut open_set = BinaryHeap::new();
+    open_set.push(FrontierNode::new(
+        heuristic.estimate(grid, start, end),
+        *start,
+    ));
+    tracker.pushed(*start, open_set.len());
+    gscore[start.y as usize][start.x as usize] = 0.0;
 
-    for y in 0..grid.size.height {
-        for x in 0..grid.size.width {
-            if start.y == y && start.x == x {
-                continue;
-            }
-
-            gscore.push((Point::new(x, y), i32::MAX))
+    while let Some(FrontierNode { point: current, .. }) = open_set.pop() {
+        if closed[current.y as usize][current.x as usize] {
+            continue;
         }
-    }
-    gscore.push((Point::new(start.x, start.y), 0));
+        closed[current.y as usize][current.x as usize] = true;
+        tracker.expanded(current);
 
-    let mut fscore = Vec::new();
-    fscore.push((Point::new(start.x, start.y), heuristic(start, end)));
-    fscore.append(&mut gscore.clone());
-    let temp_gscore = gscore.clone();
-    while !open_set.is_empty() {
-        let current = calculate_current_node(&open_set, &fscore);
-        if current.0 == *end {
-            println!("Found the goal")
+        if current == *end {
+            break;
         }
 
-        let neighbors = grid.get_surrounding_blocks(&current.0);
-        for neighbor in neighbors {
-            if neighbor.0 == GridBlock::Obstacle {
+        let gscore_of_current = gscore[current.y as usize][current.x as usize];
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            let cost = match grid.step_cost(&current, &neighbor) {
+                Some(cost) => cost,
+                None => continue,
+            };
+            if closed[neighbor.y as usize][neighbor.x as usize] {
                 continue;
             }
 
-            let gscore_of_neighbor: Option<&(Point, i32)> =
-                temp_gscore.iter().find(|x| x.0 == neighbor.1);
-            let gscore_of_current = temp_gscore.iter().find(|x| x.0 == current.0);
-            if gscore_of_neighbor.is_none() || gscore_of_current.is_none() {
-                panic!("Failed to find gscore of current of neighbor");
-            }
-            let tentative_gscore = gscore_of_current.unwrap().1 + 1;
-
-            if tentative_gscore < gscore_of_neighbor.unwrap().1 {
-                came_from.push(&gscore_of_current.unwrap().0);
-
-                gscore
-                    .iter_mut()
-                    .find(|x| x.0 == neighbor.1)
-                    .map(|x| x.1 = tentative_gscore);
-                fscore.iter_mut().find(|x| x.0 == neighbor.1).unwrap().1 =
-                    tentative_gscore + heuristic(&neighbor.1, end);
-
-                if !open_set.contains(&&neighbor.1) {
-                    open_set.push(&gscore_of_neighbor.unwrap().0);
-                }
+            let tentative_gscore = gscore_of_current + cost;
+            if tentative_gscore < gscore[neighbor.y as usize][neighbor.x as usize] {
+                came_from[neighbor.y as usize][neighbor.x as usize] = Some(current);
+                tracker.parent_updated(neighbor, current);
+                gscore[neighbor.y as usize][neighbor.x as usize] = tentative_gscore;
+                open_set.push(FrontierNode::new(
+                    tentative_gscore + heuristic.estimate(grid, &neighbor, end),
+                    neighbor,
+                ));
+                tracker.pushed(neighbor, open_set.len());
             }
         }
     }
-}
-
-fn reconstruct_path(came_from: Vec<&Point>, current: (&Point, i32)) {}
-
-// node in open_set with lowest fscore value
-fn calculate_current_node(open_set: &Vec<&Point>, fscore: &Vec<(Point, i32)>) -> (Point, i32) {
-    let mut current = open_set[0];
-    let mut current_fscore = fscore[0].1;
-    for (i, node) in open_set.iter().enumerate() {
-        if fscore[i].1 < current_fscore {
-            current = node;
-            current_fscore = fscore[i].1;
-        }
-    }
-
-    return (current.clone(), current_fscore);
-}
 
-//https://theory.stanford.edu/~amitp/GameProgramming/Heuristics.html#S7
-fn heuristic(node: &Point, goal: &Point) -> i32 {
-    let dx = node.x - goal.x;
-    let dy = node.y - goal.y;
-    let cost = 1 * (dx + dy);
-    cost
+    let path = if gscore[end.y as usize][end.x as usize] == f64::INFINITY {
+        None
+    } else {
+        Some(reconstruct_path(&came_from, end))
+    };
+    SearchResult::new(grid, path, tracker.stats)
 }
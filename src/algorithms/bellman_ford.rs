@@ -1,6 +1,70 @@
-use super::Point;
+//https://en.wikipedia.org/wiki/Bellman%E2%80%93Ford_algorithm
+
+use super::events::{SearchObserver, SearchTracker};
+use super::{reconstruct_path, Point, SearchResult};
 use crate::algorithms::GridMap;
 
-pub fn run(grid: &GridMap, start: &Point, end: &Point) {
-    unimplemented!();
-}
\ No newline at end of file
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut distance = vec![vec![f64::INFINITY; width]; height];
+    let mut parent: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+
+    distance[start.y as usize][start.x as usize] = 0.0;
+    tracker.pushed(*start, 0);
+
+    // there is no frontier, every reachable block is relaxed on each pass
+    // every edge enters a block and costs that block's movement cost, scaled for diagonal steps
+    let mut adjacency: Vec<(Point, Vec<(Point, f64)>)> = Vec::new();
+    for y in 0..grid.size.height {
+        for x in 0..grid.size.width {
+            let from = Point::new(x, y);
+            if grid.get_cost(&from).is_none() {
+                continue;
+            }
+            let edges = grid
+                .get_surrounding_blocks(&from)
+                .into_iter()
+                .filter_map(|(_, to)| grid.step_cost(&from, &to).map(|cost| (to, cost)))
+                .collect();
+            adjacency.push((from, edges));
+        }
+    }
+
+    for _ in 1..adjacency.len() {
+        let mut relaxed = false;
+        for (from, edges) in &adjacency {
+            let from_distance = distance[from.y as usize][from.x as usize];
+            if from_distance == f64::INFINITY {
+                continue;
+            }
+            tracker.expanded(*from);
+            for (to, cost) in edges {
+                if from_distance + cost < distance[to.y as usize][to.x as usize] {
+                    distance[to.y as usize][to.x as usize] = from_distance + cost;
+                    parent[to.y as usize][to.x as usize] = Some(*from);
+                    tracker.parent_updated(*to, *from);
+                    tracker.pushed(*to, 0);
+                    relaxed = true;
+                }
+            }
+        }
+        if !relaxed {
+            break;
+        }
+    }
+
+    // movement costs are all positive, so there is no negative cycle for a further pass to find
+    let path = if distance[end.y as usize][end.x as usize] == f64::INFINITY {
+        None
+    } else {
+        Some(reconstruct_path(&parent, end))
+    };
+    SearchResult::new(grid, path, tracker.stats)
+}
@@ -0,0 +1,150 @@
+//https://en.wikipedia.org/wiki/Bidirectional_search
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::WeightedHeuristic;
+use super::{join_paths, FrontierNode, Point, SearchResult};
+use crate::algorithms::GridMap;
+use std::collections::BinaryHeap;
+
+/**
+ * One half of the search, heading for the point the other half started from.
+ * The parents lead back to the point the half started from.
+ */
+struct Half {
+    goal: Point,
+    closed: Vec<Vec<bool>>,
+    gscore: Vec<Vec<f64>>,
+    came_from: Vec<Vec<Option<Point>>>,
+    open_set: BinaryHeap<FrontierNode>,
+}
+
+impl Half {
+    fn new(grid: &GridMap, origin: &Point, goal: &Point, heuristic: &WeightedHeuristic) -> Half {
+        let width = grid.size.width as usize;
+        let height = grid.size.height as usize;
+        let mut gscore = vec![vec![f64::INFINITY; width]; height];
+        gscore[origin.y as usize][origin.x as usize] = 0.0;
+        Half {
+            goal: *goal,
+            closed: vec![vec![false; width]; height],
+            gscore,
+            came_from: vec![vec![None; width]; height],
+            open_set: BinaryHeap::from([FrontierNode::new(
+                heuristic.estimate(grid, origin, goal),
+                *origin,
+            )]),
+        }
+    }
+
+    fn gscore(&self, point: &Point) -> f64 {
+        self.gscore[point.y as usize][point.x as usize]
+    }
+
+    /**
+     * The lowest fscore on the open set, no path through an open point can cost less
+     */
+    fn lowest_fscore(&self) -> f64 {
+        self.open_set
+            .peek()
+            .map_or(f64::INFINITY, |node| node.priority)
+    }
+}
+
+/**
+ * Runs A Star from both ends until the cheapest meeting point is known. The stopping rule needs
+ * the lowest fscores to be lower bounds, so any weight on the heuristic is dropped.
+ */
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    heuristic: &WeightedHeuristic,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let heuristic = &heuristic.set_weight(1.0);
+    let mut from_start = Half::new(grid, start, end, heuristic);
+    let mut from_end = Half::new(grid, end, start, heuristic);
+    let mut tracker = SearchTracker::new(observer);
+    tracker.pushed(*start, 1);
+    tracker.pushed_from_end(*end, 1);
+
+    // the cheapest path found so far through a point both halves reached
+    let mut best_cost = if start == end { 0.0 } else { f64::INFINITY };
+    let mut meeting = if start == end { Some(*start) } else { None };
+
+    // Each half's lowest fscore is a lower bound on any path still to be found, so once either
+    // reaches the best path found the search can stop. Stopping at the first meeting instead can
+    // miss a cheaper path that runs beside it.
+    while !from_start.open_set.is_empty()
+        && !from_end.open_set.is_empty()
+        && from_start.lowest_fscore().max(from_end.lowest_fscore()) < best_cost
+    {
+        let forward = from_start.open_set.len() <= from_end.open_set.len();
+        let (this, other) = if forward {
+            (&mut from_start, &from_end)
+        } else {
+            (&mut from_end, &from_start)
+        };
+
+        let current = match this.open_set.pop() {
+            Some(FrontierNode { point, .. }) => point,
+            None => break,
+        };
+        if this.closed[current.y as usize][current.x as usize] {
+            continue;
+        }
+        this.closed[current.y as usize][current.x as usize] = true;
+        if forward {
+            tracker.expanded(current);
+        } else {
+            tracker.expanded_from_end(current);
+        }
+
+        let gscore_of_current = this.gscore(&current);
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            // the half from the end walks the steps backwards, paying for the block it steps off
+            let step_cost = if forward {
+                grid.step_cost(&current, &neighbor)
+            } else if grid.is_passable(&neighbor) {
+                grid.step_cost(&neighbor, &current)
+            } else {
+                None
+            };
+            let cost = match step_cost {
+                Some(cost) => cost,
+                None => continue,
+            };
+            if this.closed[neighbor.y as usize][neighbor.x as usize] {
+                continue;
+            }
+
+            let tentative_gscore = gscore_of_current + cost;
+            if tentative_gscore < this.gscore(&neighbor) {
+                this.came_from[neighbor.y as usize][neighbor.x as usize] = Some(current);
+                tracker.parent_updated(neighbor, current);
+                this.gscore[neighbor.y as usize][neighbor.x as usize] = tentative_gscore;
+                this.open_set.push(FrontierNode::new(
+                    tentative_gscore + heuristic.estimate(grid, &neighbor, &this.goal),
+                    neighbor,
+                ));
+                if forward {
+                    tracker.pushed(neighbor, this.open_set.len());
+                } else {
+                    tracker.pushed_from_end(neighbor, this.open_set.len());
+                }
+
+                let through_neighbor = tentative_gscore + other.gscore(&neighbor);
+                if through_neighbor < best_cost {
+                    best_cost = through_neighbor;
+                    meeting = Some(neighbor);
+                }
+            }
+        }
+    }
+
+    let path = meeting.map(|meeting| {
+        tracker.met(meeting);
+        join_paths(&from_start.came_from, &from_end.came_from, &meeting)
+    });
+    SearchResult::new(grid, path, tracker.stats)
+}
@@ -0,0 +1,96 @@
+//https://en.wikipedia.org/wiki/Bidirectional_search
+
+use super::events::{SearchObserver, SearchTracker};
+use super::{join_paths, Point, SearchResult};
+use crate::algorithms::GridMap;
+use std::collections::VecDeque;
+
+/**
+ * One half of the search, the parents lead back to the point the half started from
+ */
+struct Half {
+    depth: Vec<Vec<Option<usize>>>,
+    parent: Vec<Vec<Option<Point>>>,
+    queue: VecDeque<Point>,
+}
+
+impl Half {
+    fn new(grid: &GridMap, origin: &Point) -> Half {
+        let width = grid.size.width as usize;
+        let height = grid.size.height as usize;
+        let mut depth = vec![vec![None; width]; height];
+        depth[origin.y as usize][origin.x as usize] = Some(0);
+        Half {
+            depth,
+            parent: vec![vec![None; width]; height],
+            queue: VecDeque::from([*origin]),
+        }
+    }
+
+    fn depth(&self, point: &Point) -> Option<usize> {
+        self.depth[point.y as usize][point.x as usize]
+    }
+}
+
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let mut from_start = Half::new(grid, start);
+    let mut from_end = Half::new(grid, end);
+    let mut tracker = SearchTracker::new(observer);
+    tracker.pushed(*start, 1);
+    tracker.pushed_from_end(*end, 1);
+
+    let mut meeting = if start == end { Some(*start) } else { None };
+    while meeting.is_none() && !from_start.queue.is_empty() && !from_end.queue.is_empty() {
+        // a whole level of the smaller frontier is expanded at a time, the first level that
+        // touches the other half holds the shortest path but not necessarily at its first touch
+        let forward = from_start.queue.len() <= from_end.queue.len();
+        let (this, other) = if forward {
+            (&mut from_start, &from_end)
+        } else {
+            (&mut from_end, &from_start)
+        };
+
+        let mut shortest = usize::MAX;
+        for current in std::mem::take(&mut this.queue) {
+            if forward {
+                tracker.expanded(current);
+            } else {
+                tracker.expanded_from_end(current);
+            }
+
+            let next_depth = this.depth(&current).expect("queued points have a depth") + 1;
+            for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+                if !grid.is_passable(&neighbor) || this.depth(&neighbor).is_some() {
+                    continue;
+                }
+                this.depth[neighbor.y as usize][neighbor.x as usize] = Some(next_depth);
+                this.parent[neighbor.y as usize][neighbor.x as usize] = Some(current);
+                tracker.parent_updated(neighbor, current);
+                this.queue.push_back(neighbor);
+                if forward {
+                    tracker.pushed(neighbor, this.queue.len());
+                } else {
+                    tracker.pushed_from_end(neighbor, this.queue.len());
+                }
+
+                if let Some(other_depth) = other.depth(&neighbor) {
+                    if next_depth + other_depth < shortest {
+                        shortest = next_depth + other_depth;
+                        meeting = Some(neighbor);
+                    }
+                }
+            }
+        }
+    }
+
+    let path = meeting.map(|meeting| {
+        tracker.met(meeting);
+        join_paths(&from_start.parent, &from_end.parent, &meeting)
+    });
+    SearchResult::new(grid, path, tracker.stats)
+}
@@ -1,6 +1,53 @@
-use super::Point;
+//https://en.wikipedia.org/wiki/Breadth-first_search
+
+use super::events::{SearchObserver, SearchTracker};
+use super::{reconstruct_path, Point, SearchResult};
 use crate::algorithms::GridMap;
+use std::collections::VecDeque;
+
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut visited = vec![vec![false; width]; height];
+    let mut parent: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+    let mut found = false;
+
+    let mut queue = VecDeque::new();
+    queue.push_back(*start);
+    tracker.pushed(*start, queue.len());
+    visited[start.y as usize][start.x as usize] = true;
+
+    while let Some(current) = queue.pop_front() {
+        tracker.expanded(current);
+        if current == *end {
+            found = true;
+            break;
+        }
+
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            if grid.step_cost(&current, &neighbor).is_none() {
+                continue;
+            }
+            if !visited[neighbor.y as usize][neighbor.x as usize] {
+                visited[neighbor.y as usize][neighbor.x as usize] = true;
+                parent[neighbor.y as usize][neighbor.x as usize] = Some(current);
+                tracker.parent_updated(neighbor, current);
+                queue.push_back(neighbor);
+                tracker.pushed(neighbor, queue.len());
+            }
+        }
+    }
 
-pub fn run(grid: &GridMap, start: &Point, end: &Point) {
-    unimplemented!();
-}
\ No newline at end of file
+    let path = if found {
+        Some(reconstruct_path(&parent, end))
+    } else {
+        None
+    };
+    SearchResult::new(grid, path, tracker.stats)
+}
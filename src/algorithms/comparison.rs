@@ -0,0 +1,118 @@
+use super::heuristic::WeightedHeuristic;
+use super::{Algorithm, GridMap, Pathfinder, Point, SearchResult};
+
+/**
+ * The difference in cost allowed before a path counts as longer than the optimal one
+ */
+const COST_TOLERANCE: f64 = 1e-9;
+
+pub struct Comparison {
+    pub algorithm: Algorithm,
+    pub result: SearchResult,
+}
+
+pub struct ComparisonReport {
+    pub comparisons: Vec<Comparison>,
+    pub optimal_cost: Option<f64>,
+}
+
+impl ComparisonReport {
+    /**
+     * Whether the comparison found a path costing more than the optimal one
+     */
+    pub fn is_suboptimal(&self, comparison: &Comparison) -> bool {
+        match self.optimal_cost {
+            Some(optimal_cost) => {
+                comparison.result.found()
+                    && comparison.result.total_cost > optimal_cost + COST_TOLERANCE
+            }
+            None => false,
+        }
+    }
+
+    /**
+     * Whether every compared algorithm found a path
+     */
+    pub fn all_found(&self) -> bool {
+        self.comparisons
+            .iter()
+            .all(|comparison| comparison.result.found())
+    }
+}
+
+/**
+ * Runs every algorithm on its own copy of the grid, the optimal cost comes from Dijkstra
+ * which is run separately when it is not one of the compared algorithms
+ */
+pub fn compare(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    algorithms: &[Algorithm],
+    heuristic: &WeightedHeuristic,
+) -> ComparisonReport {
+    let run = |algorithm: Algorithm| {
+        Pathfinder::new(grid.clone(), *start, *end, algorithm)
+            .set_heuristic(*heuristic)
+            .run()
+    };
+
+    let comparisons: Vec<Comparison> = algorithms
+        .iter()
+        .map(|algorithm| Comparison {
+            algorithm: *algorithm,
+            result: run(*algorithm),
+        })
+        .collect();
+
+    let optimal = match comparisons
+        .iter()
+        .find(|comparison| comparison.algorithm == Algorithm::Dijkstra)
+    {
+        Some(comparison) => comparison.result.clone(),
+        None => run(Algorithm::Dijkstra),
+    };
+
+    ComparisonReport {
+        comparisons,
+        optimal_cost: optimal.found().then_some(optimal.total_cost),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::heuristic::Heuristic;
+    use crate::map_file;
+
+    fn compare_on(map: &str, algorithms: &[Algorithm]) -> ComparisonReport {
+        let grid = map_file::from_text(map).expect("map is valid");
+        let heuristic = WeightedHeuristic::new(Heuristic::Manhattan);
+        compare(&grid, &grid.start, &grid.end, algorithms, &heuristic)
+    }
+
+    #[test]
+    fn paths_dearer_than_dijkstra_are_suboptimal() {
+        // the fewest steps cross the mud, the cheapest path goes around it
+        let report = compare_on(
+            "width 5\nheight 2\nmap\nS.m.E\n#...#\n",
+            &[Algorithm::BreadthFirstSearch, Algorithm::AStar],
+        );
+        assert_eq!(report.optimal_cost, Some(6.0));
+        assert!(report.all_found());
+        assert_eq!(report.comparisons.len(), 2);
+        assert!(report.is_suboptimal(&report.comparisons[0]));
+        assert!(!report.is_suboptimal(&report.comparisons[1]));
+    }
+
+    #[test]
+    fn unreachable_ends_have_no_optimal_cost() {
+        let report = compare_on(
+            "width 3\nheight 1\nmap\nS#E\n",
+            &[Algorithm::Dijkstra, Algorithm::AStar],
+        );
+        assert_eq!(report.optimal_cost, None);
+        assert!(!report.all_found());
+        assert!(!report.is_suboptimal(&report.comparisons[1]));
+    }
+}
@@ -0,0 +1,376 @@
+//https://en.wikipedia.org/wiki/D*
+//http://idm-lab.org/bib/abstracts/papers/aaai02b.pdf
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::WeightedHeuristic;
+use super::{Point, SearchResult};
+use crate::algorithms::GridMap;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// Keys summed along different paths can differ in the last bits, and stopping while a point on
+// the path is still queued with what is really the start's key leaves its cost out of date. Keys
+// are rounded to this precision so equal keys compare equal on the open set as well.
+const PRECISION: f64 = 1e-9;
+
+/**
+ * The priority of a point on the open set, compared by the first value and then the second
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Key(f64, f64);
+
+impl Key {
+    fn new(first: f64, second: f64) -> Key {
+        let round = |value: f64| (value / PRECISION).round() * PRECISION;
+        Key(round(first), round(second))
+    }
+
+    fn compare(&self, other: &Key) -> Ordering {
+        self.0
+            .total_cmp(&other.0)
+            .then_with(|| self.1.total_cmp(&other.1))
+    }
+
+    fn is_below(&self, other: &Key) -> bool {
+        self.compare(other) == Ordering::Less
+    }
+}
+
+/**
+ * An entry on the open set, ordered so that `BinaryHeap` pops the lowest key first
+ */
+#[derive(Debug, Clone, Copy)]
+struct KeyedNode {
+    key: Key,
+    point: Point,
+}
+
+impl PartialEq for KeyedNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for KeyedNode {}
+
+impl PartialOrd for KeyedNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KeyedNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.compare(&self.key)
+    }
+}
+
+/**
+ * A search from the end back to the start that keeps its state between calls, so when the start
+ * moves or blocks change only the part of the search they affect is done again
+ *
+ * Gscore: the cost from the point to the end the last time the point was expanded
+ * Rhs: the cost from the point to the end through its cheapest neighbour, the point is
+ * inconsistent and queued while the two differ
+ * Key modifier: how far the start has moved, added to the keys pushed since so the keys queued
+ * before the move do not have to be recalculated
+ * Pending: points whose cost to the end may have changed, updated by the next search
+ */
+pub struct DStarLite {
+    pub start: Point,
+    pub end: Point,
+    heuristic: WeightedHeuristic,
+    last_start: Point,
+    key_modifier: f64,
+    gscore: Vec<Vec<f64>>,
+    rhs: Vec<Vec<f64>>,
+    open_set: BinaryHeap<KeyedNode>,
+    queued: Vec<Vec<Option<Key>>>,
+    queued_count: usize,
+    pending: Vec<Point>,
+}
+
+impl DStarLite {
+    pub fn new(
+        grid: &GridMap,
+        start: &Point,
+        end: &Point,
+        heuristic: &WeightedHeuristic,
+    ) -> DStarLite {
+        let width = grid.size.width as usize;
+        let height = grid.size.height as usize;
+        let mut planner = DStarLite {
+            start: *start,
+            end: *end,
+            heuristic: *heuristic,
+            last_start: *start,
+            key_modifier: 0.0,
+            gscore: vec![vec![f64::INFINITY; width]; height],
+            rhs: vec![vec![f64::INFINITY; width]; height],
+            open_set: BinaryHeap::new(),
+            queued: vec![vec![None; width]; height],
+            queued_count: 0,
+            pending: vec![*end],
+        };
+        planner.rhs[end.y as usize][end.x as usize] = 0.0;
+        planner
+    }
+
+    fn gscore(&self, point: &Point) -> f64 {
+        self.gscore[point.y as usize][point.x as usize]
+    }
+
+    fn rhs(&self, point: &Point) -> f64 {
+        self.rhs[point.y as usize][point.x as usize]
+    }
+
+    fn key(&self, grid: &GridMap, point: &Point) -> Key {
+        let cost = self.gscore(point).min(self.rhs(point));
+        Key::new(
+            cost + self.heuristic.estimate(grid, &self.start, point) + self.key_modifier,
+            cost,
+        )
+    }
+
+    /**
+     * Queues the point with the key, replacing the key it was queued with before
+     */
+    fn queue(&mut self, point: &Point, key: Key) {
+        let queued = &mut self.queued[point.y as usize][point.x as usize];
+        if queued.is_none() {
+            self.queued_count += 1;
+        }
+        *queued = Some(key);
+        self.open_set.push(KeyedNode { key, point: *point });
+    }
+
+    /**
+     * Entries are left on the heap when a point is requeued or removed and skipped once they reach the top
+     */
+    fn dequeue(&mut self, point: &Point) {
+        if self.queued[point.y as usize][point.x as usize]
+            .take()
+            .is_some()
+        {
+            self.queued_count -= 1;
+        }
+    }
+
+    fn top(&mut self) -> Option<KeyedNode> {
+        while let Some(node) = self.open_set.peek() {
+            if self.queued[node.point.y as usize][node.point.x as usize] == Some(node.key) {
+                return Some(*node);
+            }
+            self.open_set.pop();
+        }
+        None
+    }
+
+    /**
+     * The cheapest cost to the end through any neighbour the point can step to
+     */
+    fn cheapest_neighbor(&self, grid: &GridMap, point: &Point) -> Option<(f64, Point)> {
+        if !grid.is_passable(point) {
+            return None;
+        }
+        let mut cheapest: Option<(f64, Point)> = None;
+        for (_, neighbor) in grid.get_surrounding_blocks(point) {
+            let cost = match grid.step_cost(point, &neighbor) {
+                Some(cost) => cost + self.gscore(&neighbor),
+                None => continue,
+            };
+            if cheapest.is_none_or(|(cheapest_cost, _)| cost < cheapest_cost) {
+                cheapest = Some((cost, neighbor));
+            }
+        }
+        cheapest
+    }
+
+    fn update_point(&mut self, grid: &GridMap, point: &Point, tracker: &mut SearchTracker) {
+        if *point != self.end {
+            self.rhs[point.y as usize][point.x as usize] = self
+                .cheapest_neighbor(grid, point)
+                .map_or(f64::INFINITY, |(cost, _)| cost);
+        }
+        self.dequeue(point);
+        if self.gscore(point) != self.rhs(point) {
+            let key = self.key(grid, point);
+            self.queue(point, key);
+            tracker.pushed(*point, self.queued_count);
+        }
+    }
+
+    /**
+     * Moves the start the path is planned from, usually one step along the path
+     */
+    pub fn move_start(&mut self, grid: &GridMap, start: &Point) {
+        self.start = *start;
+        self.key_modifier += self.heuristic.estimate(grid, &self.last_start, start);
+        self.last_start = *start;
+    }
+
+    /**
+     * Takes the points whose blocks changed on the grid, the path is repaired by the next search.
+     * Changing a block changes the cost of stepping onto it and, without corner cutting, the
+     * diagonal steps past it, so every point around it is updated.
+     */
+    pub fn update_cells(&mut self, grid: &GridMap, changed: &[Point]) {
+        for cell in changed {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let point = Point::new(cell.x + dx, cell.y + dy);
+                    if grid.contains(&point) {
+                        self.pending.push(point);
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Expands points until the start is consistent and no queued point can lower its cost, then
+     * returns the path with the counters of this call only
+     */
+    pub fn compute_path(
+        &mut self,
+        grid: &GridMap,
+        observer: &mut dyn SearchObserver,
+    ) -> SearchResult {
+        let mut tracker = SearchTracker::new(observer);
+        for point in std::mem::take(&mut self.pending) {
+            self.update_point(grid, &point, &mut tracker);
+        }
+
+        let start = self.start;
+        while let Some(top) = self.top() {
+            if !top.key.is_below(&self.key(grid, &start)) && self.rhs(&start) == self.gscore(&start)
+            {
+                break;
+            }
+
+            let current = top.point;
+            let key = self.key(grid, &current);
+            // queued before the start moved, the key is only a lower bound
+            if top.key.is_below(&key) {
+                self.queue(&current, key);
+                continue;
+            }
+
+            self.dequeue(&current);
+            tracker.expanded(current);
+            if self.gscore(&current) > self.rhs(&current) {
+                self.gscore[current.y as usize][current.x as usize] = self.rhs(&current);
+            } else {
+                self.gscore[current.y as usize][current.x as usize] = f64::INFINITY;
+                self.update_point(grid, &current, &mut tracker);
+            }
+            for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+                self.update_point(grid, &neighbor, &mut tracker);
+            }
+        }
+
+        SearchResult::new(grid, self.path(grid), tracker.stats)
+    }
+
+    /**
+     * Follows the cheapest neighbours from the start to the end, none when the start can not reach it
+     */
+    pub fn path(&self, grid: &GridMap) -> Option<Vec<Point>> {
+        if self.gscore(&self.start) == f64::INFINITY {
+            return None;
+        }
+
+        let mut path = vec![self.start];
+        let mut current = self.start;
+        while current != self.end {
+            let (cost, next) = self.cheapest_neighbor(grid, &current)?;
+            if cost == f64::INFINITY || path.len() > (grid.size.width * grid.size.height) as usize {
+                return None;
+            }
+            path.push(next);
+            current = next;
+        }
+        Some(path)
+    }
+}
+
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    heuristic: &WeightedHeuristic,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    DStarLite::new(grid, start, end, heuristic).compute_path(grid, observer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::events::NoopObserver;
+    use crate::algorithms::heuristic::Heuristic;
+    use crate::algorithms::{dijkstra, Connectivity, GridBlock};
+    use crate::map_file;
+
+    const ROWS: &str = "width 8\nheight 5\nmap\nS..g....\n.#.#.##.\n.#...#..\n.####.#.\n......#E\n";
+
+    fn assert_matches_dijkstra(planner: &mut DStarLite, grid: &GridMap) {
+        let repaired = planner.compute_path(grid, &mut NoopObserver);
+        let reference = dijkstra::run(grid, &planner.start, &planner.end, &mut NoopObserver);
+        assert_eq!(repaired.status, reference.status);
+        if reference.found() {
+            assert!((repaired.total_cost - reference.total_cost).abs() < 1e-9);
+            assert_eq!(repaired.path.first(), Some(&planner.start));
+            assert_eq!(repaired.path.last(), Some(&planner.end));
+        }
+    }
+
+    #[test]
+    fn repaired_paths_match_a_new_search() {
+        for connectivity in Connectivity::ALL {
+            let mut grid = map_file::from_text(ROWS).expect("test map is valid");
+            grid.set_connectivity(connectivity);
+            let heuristic = WeightedHeuristic::new(Heuristic::for_connectivity(connectivity));
+            let (start, end) = (grid.start, grid.end);
+            let mut planner = DStarLite::new(&grid, &start, &end, &heuristic);
+            assert_matches_dijkstra(&mut planner, &grid);
+
+            // close the way in on the right, open ways through the middle wall and beside the end,
+            // then reopen the first
+            let changes = [
+                (Point::new(7, 3), GridBlock::Obstacle),
+                (Point::new(2, 3), GridBlock::Empty),
+                (Point::new(6, 4), GridBlock::Empty),
+                (Point::new(7, 3), GridBlock::Empty),
+            ];
+            for (point, block) in changes {
+                grid.set_block(&point, &block);
+                planner.update_cells(&grid, &[point]);
+                assert_matches_dijkstra(&mut planner, &grid);
+            }
+
+            let next = planner.compute_path(&grid, &mut NoopObserver).path[1];
+            planner.move_start(&grid, &next);
+            // then wall the end in after a step
+            let walls = [Point::new(6, 3), Point::new(7, 3), Point::new(6, 4)];
+            for point in walls {
+                grid.set_block(&point, &GridBlock::Obstacle);
+            }
+            planner.update_cells(&grid, &walls);
+            assert_matches_dijkstra(&mut planner, &grid);
+            assert!(!planner.compute_path(&grid, &mut NoopObserver).found());
+        }
+    }
+
+    #[test]
+    fn moving_the_start_needs_no_new_expansions() {
+        let grid = map_file::from_text(ROWS).expect("test map is valid");
+        let heuristic = WeightedHeuristic::new(Heuristic::Manhattan);
+        let mut planner = DStarLite::new(&grid, &grid.start, &grid.end, &heuristic);
+        let first = planner.compute_path(&grid, &mut NoopObserver);
+        planner.move_start(&grid, &first.path[1]);
+        let second = planner.compute_path(&grid, &mut NoopObserver);
+        assert_eq!(second.nodes_expanded, 0);
+        assert_eq!(second.path, first.path[1..]);
+    }
+}
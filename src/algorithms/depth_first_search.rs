@@ -1,6 +1,55 @@
-use super::Point;
+//https://en.wikipedia.org/wiki/Depth-first_search
+
+use super::events::{SearchObserver, SearchTracker};
+use super::{reconstruct_path, Point, SearchResult};
 use crate::algorithms::GridMap;
 
-pub fn run(grid: &GridMap, start: &Point, end: &Point) {
-    unimplemented!();
-}
\ No newline at end of file
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut visited = vec![vec![false; width]; height];
+    let mut parent: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+    let mut found = false;
+
+    let mut stack = vec![*start];
+    tracker.pushed(*start, stack.len());
+
+    while let Some(current) = stack.pop() {
+        if visited[current.y as usize][current.x as usize] {
+            continue;
+        }
+        visited[current.y as usize][current.x as usize] = true;
+        tracker.expanded(current);
+
+        if current == *end {
+            found = true;
+            break;
+        }
+
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            if grid.step_cost(&current, &neighbor).is_none() {
+                continue;
+            }
+            // a node keeps the parent that pushed it last, which is the one it is popped from
+            if !visited[neighbor.y as usize][neighbor.x as usize] {
+                parent[neighbor.y as usize][neighbor.x as usize] = Some(current);
+                tracker.parent_updated(neighbor, current);
+                stack.push(neighbor);
+                tracker.pushed(neighbor, stack.len());
+            }
+        }
+    }
+
+    let path = if found {
+        Some(reconstruct_path(&parent, end))
+    } else {
+        None
+    };
+    SearchResult::new(grid, path, tracker.stats)
+}
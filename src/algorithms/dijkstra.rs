@@ -1,65 +1,64 @@
-use super::{GridBlock, Point};
+//https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm
+
+use super::events::{SearchObserver, SearchTracker};
+use super::{reconstruct_path, FrontierNode, Point, SearchResult};
 use crate::algorithms::GridMap;
+use std::collections::BinaryHeap;
 
-pub fn run(mut grid: GridMap, start: &Point, end: &Point) {
-    let mut visited: Vec<Vec<bool>> = Vec::new();
-    let mut distance: Vec<Vec<i32>> = Vec::new();
-    let mut parent: Vec<Vec<Point>> = Vec::new();
-    for _ in 0..grid.size.height {
-        let mut row_vis = Vec::new();
-        let mut row_dis = Vec::new();
-        let mut row_par = Vec::new();
-        for _ in 0..grid.size.width {
-            row_vis.push(false);
-            row_dis.push(i32::MAX);
-            row_par.push(Point::new(0, 0));
-        }
-        visited.push(row_vis);
-        distance.push(row_dis);
-        parent.push(row_par);
-    }
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut visited = vec![vec![false; width]; height];
+    let mut distance = vec![vec![f64::INFINITY; width]; height];
+    let mut parent: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
 
-    let mut queue: Vec<Point> = Vec::new();
-    queue.push(start.clone());
-    visited[start.y as usize][start.x as usize] = true;
-    distance[start.y as usize][start.x as usize] = 0;
+    let mut frontier = BinaryHeap::new();
+    frontier.push(FrontierNode::new(0.0, *start));
+    tracker.pushed(*start, frontier.len());
+    distance[start.y as usize][start.x as usize] = 0.0;
 
-    while !queue.is_empty() {
-        let current = queue.remove(0);
-        if current.x == end.x && current.y == end.y {
+    while let Some(FrontierNode { priority, point }) = frontier.pop() {
+        // stale entries are left in the heap instead of being decreased in place
+        if visited[point.y as usize][point.x as usize] {
+            continue;
+        }
+        visited[point.y as usize][point.x as usize] = true;
+        tracker.expanded(point);
+
+        if point == *end {
             break;
         }
 
-        let surrounding_blocks = grid.get_surrounding_blocks(&current);
-        for (block, point) in surrounding_blocks {
-            if block == GridBlock::Obstacle {
+        for (_, neighbor) in grid.get_surrounding_blocks(&point) {
+            let cost = match grid.step_cost(&point, &neighbor) {
+                Some(cost) => cost,
+                None => continue,
+            };
+            if visited[neighbor.y as usize][neighbor.x as usize] {
                 continue;
             }
-            if !visited[point.y as usize][point.x as usize] {
-                visited[point.y as usize][point.x as usize] = true;
-                distance[point.y as usize][point.x as usize] =
-                    distance[current.y as usize][current.x as usize] + 1;
-                parent[point.y as usize][point.x as usize] = current;
-                queue.push(point);
+
+            let tentative_distance = priority + cost;
+            if tentative_distance < distance[neighbor.y as usize][neighbor.x as usize] {
+                distance[neighbor.y as usize][neighbor.x as usize] = tentative_distance;
+                parent[neighbor.y as usize][neighbor.x as usize] = Some(point);
+                tracker.parent_updated(neighbor, point);
+                frontier.push(FrontierNode::new(tentative_distance, neighbor));
+                tracker.pushed(neighbor, frontier.len());
             }
         }
     }
 
-    let mut current = end.clone();
-
-    if distance[end.y as usize][end.x as usize] == i32::MAX {
-        grid.render();
-        println!("No path found");
-        return;
-    }
-
-    while current.x != start.x || current.y != start.y {
-        grid.set_block(&current, &GridBlock::Path);
-        current = parent[current.y as usize][current.x as usize];
-    }
-
-    grid.set_block(&start, &GridBlock::Start);
-    grid.set_block(&end, &GridBlock::End);
-    grid.render();
-    println!("Path Length: {}", distance[end.y as usize][end.x as usize]);
+    let path = if distance[end.y as usize][end.x as usize] == f64::INFINITY {
+        None
+    } else {
+        Some(reconstruct_path(&parent, end))
+    };
+    SearchResult::new(grid, path, tracker.stats)
 }
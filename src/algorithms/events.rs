@@ -0,0 +1,120 @@
+use super::{Point, SearchStats};
+
+/**
+ * A single step taken by a search, emitted in the order the algorithm performs it
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchEvent {
+    Pushed(Point),
+    Expanded(Point),
+    ParentUpdated { point: Point, parent: Point },
+    JumpPoint(Point),
+    PushedFromEnd(Point),
+    ExpandedFromEnd(Point),
+    Met(Point),
+    Iteration { threshold: f64 },
+    PathFound(Vec<Point>),
+    Waypoints(Vec<Point>),
+}
+
+pub trait SearchObserver {
+    fn on_event(&mut self, event: SearchEvent);
+}
+
+/**
+ * Ignores every event, used when only the result of a search is needed
+ */
+pub struct NoopObserver;
+
+impl SearchObserver for NoopObserver {
+    fn on_event(&mut self, _event: SearchEvent) {}
+}
+
+/**
+ * Keeps every event so the search can be played back after it finishes
+ */
+#[derive(Debug, Default, Clone)]
+pub struct EventRecorder {
+    pub events: Vec<SearchEvent>,
+}
+
+impl EventRecorder {
+    pub fn new() -> EventRecorder {
+        EventRecorder::default()
+    }
+}
+
+impl SearchObserver for EventRecorder {
+    fn on_event(&mut self, event: SearchEvent) {
+        self.events.push(event);
+    }
+}
+
+/**
+ * Updates the search stats and forwards the matching event to the observer
+ */
+pub struct SearchTracker<'a> {
+    pub stats: SearchStats,
+    observer: &'a mut dyn SearchObserver,
+}
+
+impl<'a> SearchTracker<'a> {
+    pub fn new(observer: &'a mut dyn SearchObserver) -> SearchTracker<'a> {
+        SearchTracker {
+            stats: SearchStats::new(),
+            observer,
+        }
+    }
+
+    pub fn pushed(&mut self, point: Point, frontier_size: usize) {
+        self.stats.nodes_generated += 1;
+        self.stats.record_frontier(frontier_size);
+        self.observer.on_event(SearchEvent::Pushed(point));
+    }
+
+    pub fn expanded(&mut self, point: Point) {
+        self.stats.nodes_expanded += 1;
+        self.observer.on_event(SearchEvent::Expanded(point));
+    }
+
+    pub fn parent_updated(&mut self, point: Point, parent: Point) {
+        self.observer
+            .on_event(SearchEvent::ParentUpdated { point, parent });
+    }
+
+    /**
+     * The backward half of a bidirectional search, counted the same as the forward half
+     */
+    pub fn pushed_from_end(&mut self, point: Point, frontier_size: usize) {
+        self.stats.nodes_generated += 1;
+        self.stats.record_frontier(frontier_size);
+        self.observer.on_event(SearchEvent::PushedFromEnd(point));
+    }
+
+    pub fn expanded_from_end(&mut self, point: Point) {
+        self.stats.nodes_expanded += 1;
+        self.observer.on_event(SearchEvent::ExpandedFromEnd(point));
+    }
+
+    /**
+     * The point where the two halves of a bidirectional search joined the path
+     */
+    pub fn met(&mut self, point: Point) {
+        self.observer.on_event(SearchEvent::Met(point));
+    }
+
+    /**
+     * An iterative search starting over from the start, nothing painted before it is still searched
+     */
+    pub fn iteration(&mut self, threshold: f64) {
+        self.stats.iterations += 1;
+        self.observer.on_event(SearchEvent::Iteration { threshold });
+    }
+
+    /**
+     * Marks a point a jump point search landed on, sent after it is pushed
+     */
+    pub fn jump_point(&mut self, point: Point) {
+        self.observer.on_event(SearchEvent::JumpPoint(point));
+    }
+}
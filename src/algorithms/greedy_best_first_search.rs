@@ -1,6 +1,62 @@
-use super::Point;
+//https://en.wikipedia.org/wiki/Best-first_search#Greedy_BFS
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::WeightedHeuristic;
+use super::{reconstruct_path, FrontierNode, Point, SearchResult};
 use crate::algorithms::GridMap;
+use std::collections::BinaryHeap;
+
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    heuristic: &WeightedHeuristic,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut visited = vec![vec![false; width]; height];
+    let mut parent: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+    let mut found = false;
+
+    // only the estimated distance to the goal is used, the cost so far is ignored
+    let mut frontier = BinaryHeap::new();
+    frontier.push(FrontierNode::new(
+        heuristic.estimate(grid, start, end),
+        *start,
+    ));
+    tracker.pushed(*start, frontier.len());
+    visited[start.y as usize][start.x as usize] = true;
+
+    while let Some(FrontierNode { point: current, .. }) = frontier.pop() {
+        tracker.expanded(current);
+        if current == *end {
+            found = true;
+            break;
+        }
+
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            if grid.step_cost(&current, &neighbor).is_none() {
+                continue;
+            }
+            if !visited[neighbor.y as usize][neighbor.x as usize] {
+                visited[neighbor.y as usize][neighbor.x as usize] = true;
+                parent[neighbor.y as usize][neighbor.x as usize] = Some(current);
+                tracker.parent_updated(neighbor, current);
+                frontier.push(FrontierNode::new(
+                    heuristic.estimate(grid, &neighbor, end),
+                    neighbor,
+                ));
+                tracker.pushed(neighbor, frontier.len());
+            }
+        }
+    }
 
-pub fn run(grid: &GridMap, start: &Point, end: &Point) {
-    unimplemented!();
-}
\ No newline at end of file
+    let path = if found {
+        Some(reconstruct_path(&parent, end))
+    } else {
+        None
+    };
+    SearchResult::new(grid, path, tracker.stats)
+}
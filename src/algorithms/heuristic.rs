@@ -0,0 +1,177 @@
+//https://theory.stanford.edu/~amitp/GameProgramming/Heuristics.html
+
+use super::{Connectivity, GridMap, Point};
+use std::f64::consts::SQRT_2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    Manhattan,
+    Euclidean,
+    Chebyshev,
+    Octile,
+    Zero,
+}
+
+impl Heuristic {
+    pub const ALL: [Heuristic; 5] = [
+        Heuristic::Manhattan,
+        Heuristic::Euclidean,
+        Heuristic::Chebyshev,
+        Heuristic::Octile,
+        Heuristic::Zero,
+    ];
+
+    /**
+     * The distance matching the connectivity, manhattan for 4-way grids and octile when diagonals are allowed
+     */
+    pub fn for_connectivity(connectivity: Connectivity) -> Heuristic {
+        match connectivity {
+            Connectivity::FourWay => Heuristic::Manhattan,
+            Connectivity::EightWay | Connectivity::EightWayNoCornerCutting => Heuristic::Octile,
+        }
+    }
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            Heuristic::Manhattan => "Manhattan",
+            Heuristic::Euclidean => "Euclidean",
+            Heuristic::Chebyshev => "Chebyshev",
+            Heuristic::Octile => "Octile",
+            Heuristic::Zero => "Zero",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Heuristic> {
+        Heuristic::ALL
+            .into_iter()
+            .find(|heuristic| heuristic.to_name().eq_ignore_ascii_case(name))
+    }
+
+    /**
+     * Estimates the remaining cost from a node to the goal, diagonal steps use the grid's diagonal cost.
+     * The straight line is shortened when diagonals cost less than its length, so it stays below
+     * the octile distance.
+     */
+    pub fn estimate(&self, grid: &GridMap, node: &Point, goal: &Point) -> f64 {
+        let dx = (node.x - goal.x).abs() as f64;
+        let dy = (node.y - goal.y).abs() as f64;
+        match self {
+            Heuristic::Manhattan => dx + dy,
+            Heuristic::Euclidean => {
+                (dx * dx + dy * dy).sqrt() * (grid.diagonal_cost / SQRT_2).min(1.0)
+            }
+            Heuristic::Chebyshev => dx.max(dy),
+            Heuristic::Octile => dx.max(dy) + (grid.diagonal_cost - 1.0) * dx.min(dy),
+            Heuristic::Zero => 0.0,
+        }
+    }
+}
+
+/**
+ * A heuristic scaled by a weight, weights above 1 trade optimality for fewer expansions
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedHeuristic {
+    pub heuristic: Heuristic,
+    pub weight: f64,
+}
+
+impl WeightedHeuristic {
+    pub fn new(heuristic: Heuristic) -> Self {
+        WeightedHeuristic {
+            heuristic,
+            weight: 1.0,
+        }
+    }
+
+    pub fn set_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn estimate(&self, grid: &GridMap, node: &Point, goal: &Point) -> f64 {
+        self.weight * self.heuristic.estimate(grid, node, goal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Algorithm, Pathfinder};
+    use crate::map_file;
+
+    const MAP: &str = "width 8\nheight 5\nmap\nS..g....\n.#.#.##.\n.#s..#..\n.####.#w\n......#E\n";
+
+    /**
+     * The heuristics that never overestimate with the connectivity, manhattan counts a diagonal
+     * as two steps
+     */
+    fn admissible(connectivity: Connectivity) -> Vec<Heuristic> {
+        Heuristic::ALL
+            .into_iter()
+            .filter(|heuristic| {
+                connectivity == Connectivity::FourWay || *heuristic != Heuristic::Manhattan
+            })
+            .collect()
+    }
+
+    #[test]
+    fn admissible_heuristics_never_overestimate() {
+        for connectivity in Connectivity::ALL {
+            for diagonal_cost in [1.0, SQRT_2, 2.0] {
+                let mut grid = map_file::from_text(MAP).expect("map is valid");
+                grid.set_connectivity(connectivity);
+                grid.set_diagonal_cost(diagonal_cost);
+                let goal = grid.end;
+                for y in 0..grid.size.height {
+                    for x in 0..grid.size.width {
+                        let point = Point::new(x, y);
+                        if !grid.is_passable(&point) {
+                            continue;
+                        }
+                        let cost = Pathfinder::new(grid.clone(), point, goal, Algorithm::Dijkstra)
+                            .run()
+                            .total_cost;
+                        for heuristic in admissible(connectivity) {
+                            assert!(
+                                heuristic.estimate(&grid, &point, &goal) <= cost + 1e-9,
+                                "{} from {:?} with {:?} and diagonal cost {}",
+                                heuristic.to_name(),
+                                point,
+                                connectivity,
+                                diagonal_cost
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_connectivity_heuristic_is_exact_on_open_ground() {
+        let grid_for = |connectivity| {
+            let mut grid =
+                map_file::from_text("width 6\nheight 4\nmap\nS.....\n......\n......\n.....E\n")
+                    .expect("map is valid");
+            grid.set_connectivity(connectivity);
+            grid
+        };
+        for connectivity in Connectivity::ALL {
+            let grid = grid_for(connectivity);
+            let heuristic = Heuristic::for_connectivity(connectivity);
+            assert!(admissible(connectivity).contains(&heuristic));
+            let cost = Pathfinder::new(grid.clone(), grid.start, grid.end, Algorithm::Dijkstra)
+                .run()
+                .total_cost;
+            assert!((heuristic.estimate(&grid, &grid.start, &grid.end) - cost).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn weights_scale_the_estimate() {
+        let grid = map_file::from_text(MAP).expect("map is valid");
+        let weighted = WeightedHeuristic::new(Heuristic::Manhattan).set_weight(2.5);
+        assert_eq!(weighted.estimate(&grid, &grid.start, &grid.end), 2.5 * 11.0);
+    }
+}
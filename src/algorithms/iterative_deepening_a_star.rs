@@ -0,0 +1,216 @@
+//https://en.wikipedia.org/wiki/Iterative_deepening_A*
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::WeightedHeuristic;
+use super::{Point, SearchResult};
+use crate::algorithms::GridMap;
+use crate::generation::components::Components;
+
+// fscores summed along different paths can differ in the last bits, they are still the same bound
+const TOLERANCE: f64 = 1e-9;
+
+/**
+ * The least the threshold is raised by, no step costs less so it is passed by a path one step longer
+ */
+const MIN_RAISE: f64 = 1.0;
+
+/**
+ * The most points the transposition table remembers, grids with more blocks share its slots
+ */
+pub const TABLE_SLOTS: usize = 1 << 14;
+
+/**
+ * The last expansion of a point, the iteration it was in and the gscore it was reached with
+ */
+#[derive(Clone, Copy)]
+struct Entry {
+    point: Point,
+    iteration: i32,
+    gscore: f64,
+}
+
+/**
+ * The last expansion of each point, kept in a fixed number of slots picked by the point's index on
+ * the grid. A point that lands on a taken slot replaces the point there, which only loses a cutoff,
+ * so memory stays bounded however large the grid is.
+ */
+struct TranspositionTable {
+    width: i32,
+    slots: Vec<Option<Entry>>,
+}
+
+impl TranspositionTable {
+    fn new(grid: &GridMap) -> TranspositionTable {
+        let cells = (grid.size.width * grid.size.height) as usize;
+        TranspositionTable {
+            width: grid.size.width,
+            slots: vec![None; cells.clamp(1, TABLE_SLOTS)],
+        }
+    }
+
+    fn slot(&self, point: &Point) -> usize {
+        (point.y * self.width + point.x) as usize % self.slots.len()
+    }
+
+    /**
+     * The last expansion of the point, if its slot still holds it
+     */
+    fn get(&self, point: &Point) -> Option<Entry> {
+        self.slots[self.slot(point)].filter(|entry| entry.point == *point)
+    }
+
+    fn insert(&mut self, point: Point, iteration: i32, gscore: f64) {
+        let slot = self.slot(&point);
+        self.slots[slot] = Some(Entry {
+            point,
+            iteration,
+            gscore,
+        });
+    }
+}
+
+/**
+ * A point on the path the depth first search is following, with the neighbours it still has to try
+ */
+struct Step {
+    point: Point,
+    neighbors: Vec<(f64, Point)>,
+}
+
+impl Step {
+    /**
+     * The neighbours are tried with the most promising first so the goal is reached early in the
+     * final iteration
+     */
+    fn new(
+        grid: &GridMap,
+        point: Point,
+        gscore: f64,
+        end: &Point,
+        heuristic: &WeightedHeuristic,
+    ) -> Step {
+        let mut neighbors: Vec<(f64, Point)> = grid
+            .get_surrounding_blocks(&point)
+            .into_iter()
+            .filter_map(|(_, neighbor)| {
+                let cost = grid.step_cost(&point, &neighbor)?;
+                Some((gscore + cost, neighbor))
+            })
+            .collect();
+        neighbors.sort_by(|(a_gscore, a), (b_gscore, b)| {
+            let a_fscore = a_gscore + heuristic.estimate(grid, a, end);
+            let b_fscore = b_gscore + heuristic.estimate(grid, b, end);
+            b_fscore.total_cmp(&a_fscore)
+        });
+        Step { point, neighbors }
+    }
+}
+
+/**
+ * Depth first searches that give up on any path whose fscore passes the threshold, every
+ * iteration starts over from the start with the threshold raised to the lowest fscore the last
+ * one gave up on.
+ *
+ * Only the path being followed is kept, with the neighbours each point on it still has to try, and
+ * a transposition table of at most `TABLE_SLOTS` points. Without the table two paths reaching the
+ * same point would both be searched from it and the number of paths grows exponentially, with it a
+ * point already reached for less in the same iteration is cut off. Points are still expanded again
+ * in every iteration, and again within one when a cheaper path turns up or the table forgot them.
+ * Expanding a point the table holds counts as a re-expansion, which is exact while every block of
+ * the grid has a slot of its own.
+ *
+ * Diagonals give nearly every path its own fscore, so raising the threshold only to the lowest one
+ * given up on takes an iteration for each of them. It is raised by at least `MIN_RAISE` instead,
+ * which can let the last iteration reach the end along a path that is not the cheapest, so that
+ * iteration keeps searching for cheaper paths than the best found until none are left.
+ *
+ * The iterations only run out once the threshold passes every path from the start, so an end that
+ * can not be reached is ruled out before searching.
+ */
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    heuristic: &WeightedHeuristic,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let mut tracker = SearchTracker::new(observer);
+    if !Components::new(grid).connected(start, end) {
+        return SearchResult::new(grid, None, tracker.stats);
+    }
+
+    let mut table = TranspositionTable::new(grid);
+    let mut threshold = heuristic.estimate(grid, start, end);
+
+    loop {
+        tracker.iteration(threshold);
+        let iteration = tracker.stats.iterations;
+        tracker.pushed(*start, 1);
+        tracker.expanded(*start);
+        if table.get(start).is_some() {
+            tracker.stats.re_expansions += 1;
+        }
+        if start == end {
+            return SearchResult::new(grid, Some(vec![*start]), tracker.stats);
+        }
+
+        table.insert(*start, iteration, 0.0);
+        let mut stack = vec![Step::new(grid, *start, 0.0, end, heuristic)];
+        let mut next_threshold = f64::INFINITY;
+        let mut best: Option<(f64, Vec<Point>)> = None;
+
+        while let Some(step) = stack.last_mut() {
+            let (gscore, neighbor) = match step.neighbors.pop() {
+                Some(next) => next,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+            // walking back onto the path would only loop, and a point already searched from for
+            // less has nothing left to find
+            let expanded_before = table.get(&neighbor);
+            if expanded_before.is_some_and(|entry| {
+                entry.iteration == iteration && gscore + TOLERANCE >= entry.gscore
+            }) || stack.iter().any(|step| step.point == neighbor)
+            {
+                continue;
+            }
+
+            tracker.pushed(neighbor, stack.len() + 1);
+            let fscore = gscore + heuristic.estimate(grid, &neighbor, end);
+            if best
+                .as_ref()
+                .is_some_and(|(cost, _)| fscore + TOLERANCE >= *cost)
+            {
+                continue;
+            }
+            if fscore > threshold + TOLERANCE {
+                next_threshold = next_threshold.min(fscore);
+                continue;
+            }
+
+            table.insert(neighbor, iteration, gscore);
+            tracker.expanded(neighbor);
+            if expanded_before.is_some() {
+                tracker.stats.re_expansions += 1;
+            }
+            if neighbor == *end {
+                let mut path: Vec<Point> = stack.iter().map(|step| step.point).collect();
+                path.push(neighbor);
+                best = Some((gscore, path));
+                continue;
+            }
+            stack.push(Step::new(grid, neighbor, gscore, end, heuristic));
+        }
+
+        if let Some((_, path)) = best {
+            return SearchResult::new(grid, Some(path), tracker.stats);
+        }
+        // nothing was cut off, every path from the start has been followed to its end
+        if next_threshold == f64::INFINITY {
+            return SearchResult::new(grid, None, tracker.stats);
+        }
+        threshold = next_threshold.max(threshold + MIN_RAISE);
+    }
+}
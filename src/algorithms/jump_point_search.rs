@@ -0,0 +1,249 @@
+//https://en.wikipedia.org/wiki/Jump_point_search
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::WeightedHeuristic;
+use super::{FrontierNode, Point, SearchResult};
+use crate::algorithms::{Connectivity, GridMap};
+use std::collections::BinaryHeap;
+
+pub const FALLBACK_WARNING: &str =
+    "Jump Point Search needs 8-way movement, a diagonal cost under 2 and no weighted terrain, A Star is run instead";
+
+/**
+ * Jump point search only finds shortest paths on grids where every passable block costs the same
+ * and diagonal moves are allowed. Its pruning also takes a diagonal to cost no less than a straight
+ * move and less than the two straight moves it replaces.
+ */
+pub fn supports(grid: &GridMap) -> bool {
+    grid.connectivity != Connectivity::FourWay
+        && (1.0..2.0).contains(&grid.diagonal_cost)
+        && grid
+            .grid
+            .iter()
+            .flatten()
+            .all(|element| element.grid.movement_cost().is_none_or(|cost| cost == 1.0))
+}
+
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    heuristic: &WeightedHeuristic,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut closed = vec![vec![false; width]; height];
+    let mut gscore = vec![vec![f64::INFINITY; width]; height];
+    let mut came_from: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+
+    // only jump points enter the open set, the blocks jumped over are never stored
+    let mut open_set = BinaryHeap::new();
+    open_set.push(FrontierNode::new(
+        heuristic.estimate(grid, start, end),
+        *start,
+    ));
+    tracker.pushed(*start, open_set.len());
+    gscore[start.y as usize][start.x as usize] = 0.0;
+
+    while let Some(FrontierNode { point: current, .. }) = open_set.pop() {
+        if closed[current.y as usize][current.x as usize] {
+            continue;
+        }
+        closed[current.y as usize][current.x as usize] = true;
+        tracker.expanded(current);
+
+        if current == *end {
+            break;
+        }
+
+        let parent = came_from[current.y as usize][current.x as usize];
+        let gscore_of_current = gscore[current.y as usize][current.x as usize];
+        for direction in pruned_directions(grid, &current, parent.as_ref()) {
+            let jump_point = match jump(grid, &current, direction, end) {
+                Some(jump_point) => jump_point,
+                None => continue,
+            };
+            if closed[jump_point.y as usize][jump_point.x as usize] {
+                continue;
+            }
+
+            let tentative_gscore = gscore_of_current + distance(grid, &current, &jump_point);
+            if tentative_gscore < gscore[jump_point.y as usize][jump_point.x as usize] {
+                came_from[jump_point.y as usize][jump_point.x as usize] = Some(current);
+                tracker.parent_updated(jump_point, current);
+                gscore[jump_point.y as usize][jump_point.x as usize] = tentative_gscore;
+                open_set.push(FrontierNode::new(
+                    tentative_gscore + heuristic.estimate(grid, &jump_point, end),
+                    jump_point,
+                ));
+                tracker.pushed(jump_point, open_set.len());
+                tracker.jump_point(jump_point);
+            }
+        }
+    }
+
+    let path = if gscore[end.y as usize][end.x as usize] == f64::INFINITY {
+        None
+    } else {
+        Some(fill_in_path(&came_from, end))
+    };
+    SearchResult::new(grid, path, tracker.stats)
+}
+
+/**
+ * Whether the step from the point in the direction can be taken, diagonal steps that cut the
+ * corner of an obstacle are only allowed when the grid allows corner cutting
+ */
+fn can_step(grid: &GridMap, point: &Point, (dx, dy): (i32, i32)) -> bool {
+    if !grid.is_passable(&Point::new(point.x + dx, point.y + dy)) {
+        return false;
+    }
+    dx == 0
+        || dy == 0
+        || grid.connectivity != Connectivity::EightWayNoCornerCutting
+        || (grid.is_passable(&Point::new(point.x + dx, point.y))
+            && grid.is_passable(&Point::new(point.x, point.y + dy)))
+}
+
+fn is_blocked(grid: &GridMap, x: i32, y: i32) -> bool {
+    !grid.is_passable(&Point::new(x, y))
+}
+
+/**
+ * The directions out of a point that no path through its parent reaches more cheaply, split into
+ * the natural directions that continue the move and the forced ones next to obstacles
+ */
+fn pruned_directions(grid: &GridMap, point: &Point, parent: Option<&Point>) -> Vec<(i32, i32)> {
+    let parent = match parent {
+        Some(parent) => parent,
+        None => {
+            return grid
+                .get_surrounding_blocks(point)
+                .into_iter()
+                .map(|(_, neighbor)| (neighbor.x - point.x, neighbor.y - point.y))
+                .collect()
+        }
+    };
+
+    let dx = (point.x - parent.x).signum();
+    let dy = (point.y - parent.y).signum();
+    let mut directions = if dx != 0 && dy != 0 {
+        vec![(dx, 0), (0, dy), (dx, dy)]
+    } else {
+        vec![(dx, dy)]
+    };
+    directions.extend(forced_directions(grid, point, (dx, dy)));
+    directions
+        .into_iter()
+        .filter(|direction| can_step(grid, point, *direction))
+        .collect()
+}
+
+/**
+ * Directions that are only worth taking because an obstacle next to the point blocks the
+ * cheaper way around it
+ */
+fn forced_directions(grid: &GridMap, point: &Point, (dx, dy): (i32, i32)) -> Vec<(i32, i32)> {
+    let (x, y) = (point.x, point.y);
+    let mut forced = Vec::new();
+    let corner_cutting = grid.connectivity != Connectivity::EightWayNoCornerCutting;
+
+    if dx != 0 && dy != 0 {
+        // without corner cutting both sides of a diagonal move are open, so nothing is forced
+        if corner_cutting {
+            if is_blocked(grid, x - dx, y) {
+                forced.push((-dx, dy));
+            }
+            if is_blocked(grid, x, y - dy) {
+                forced.push((dx, -dy));
+            }
+        }
+    } else if dx != 0 {
+        for side in [-1, 1] {
+            if corner_cutting {
+                if is_blocked(grid, x, y + side) {
+                    forced.push((dx, side));
+                }
+            } else if is_blocked(grid, x - dx, y + side) {
+                forced.push((0, side));
+                forced.push((dx, side));
+            }
+        }
+    } else {
+        for side in [-1, 1] {
+            if corner_cutting {
+                if is_blocked(grid, x + side, y) {
+                    forced.push((side, dy));
+                }
+            } else if is_blocked(grid, x + side, y - dy) {
+                forced.push((side, 0));
+                forced.push((side, dy));
+            }
+        }
+    }
+    forced
+}
+
+fn has_forced_neighbor(grid: &GridMap, point: &Point, direction: (i32, i32)) -> bool {
+    forced_directions(grid, point, direction)
+        .into_iter()
+        .any(|forced| can_step(grid, point, forced))
+}
+
+/**
+ * Steps from the point in the direction until reaching the end, a point with a forced neighbour,
+ * or for diagonal moves a point where a straight jump finds one. Returns `None` on running into
+ * an obstacle or the edge of the grid.
+ */
+fn jump(grid: &GridMap, from: &Point, (dx, dy): (i32, i32), end: &Point) -> Option<Point> {
+    let mut current = *from;
+    loop {
+        if !can_step(grid, &current, (dx, dy)) {
+            return None;
+        }
+        current = Point::new(current.x + dx, current.y + dy);
+        if current == *end || has_forced_neighbor(grid, &current, (dx, dy)) {
+            return Some(current);
+        }
+        if dx != 0
+            && dy != 0
+            && (jump(grid, &current, (dx, 0), end).is_some()
+                || jump(grid, &current, (0, dy), end).is_some())
+        {
+            return Some(current);
+        }
+    }
+}
+
+/**
+ * The cost of the straight or diagonal line between two jump points
+ */
+fn distance(grid: &GridMap, from: &Point, to: &Point) -> f64 {
+    let steps = (to.x - from.x).abs().max((to.y - from.y).abs()) as f64;
+    if from.x != to.x && from.y != to.y {
+        steps * grid.diagonal_cost
+    } else {
+        steps
+    }
+}
+
+/**
+ * Walks the parent links back from the end and fills in the blocks jumped over between each pair
+ * of jump points, so the path has every block like the paths of the other algorithms
+ */
+fn fill_in_path(came_from: &[Vec<Option<Point>>], end: &Point) -> Vec<Point> {
+    let mut path = vec![*end];
+    let mut current = *end;
+    while let Some(previous) = came_from[current.y as usize][current.x as usize] {
+        let dx = (previous.x - current.x).signum();
+        let dy = (previous.y - current.y).signum();
+        while current != previous {
+            current = Point::new(current.x + dx, current.y + dy);
+            path.push(current);
+        }
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,105 @@
+//https://en.wikipedia.org/wiki/Theta*
+//http://idm-lab.org/bib/abstracts/papers/aaai10b.pdf
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::Heuristic;
+use super::line_of_sight::{line_of_sight, segment_cost};
+use super::{reconstruct_path, FrontierNode, Point, SearchResult};
+use crate::algorithms::GridMap;
+use std::collections::BinaryHeap;
+
+/**
+ * Theta Star that assumes every neighbour can be seen from the parent when it is pushed and only
+ * checks the line of sight once the neighbour is expanded, most pushed points never are
+ */
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut closed = vec![vec![false; width]; height];
+    let mut gscore = vec![vec![f64::INFINITY; width]; height];
+    let mut came_from: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+
+    let mut open_set = BinaryHeap::new();
+    open_set.push(FrontierNode::new(
+        Heuristic::Euclidean.estimate(grid, start, end),
+        *start,
+    ));
+    tracker.pushed(*start, open_set.len());
+    gscore[start.y as usize][start.x as usize] = 0.0;
+
+    while let Some(FrontierNode { point: current, .. }) = open_set.pop() {
+        if closed[current.y as usize][current.x as usize] {
+            continue;
+        }
+        closed[current.y as usize][current.x as usize] = true;
+        tracker.expanded(current);
+
+        // the parent was only assumed to be in sight, check it and settle the real cost or fall
+        // back to the cheapest neighbour that is already expanded
+        if let Some(parent) = came_from[current.y as usize][current.x as usize] {
+            if line_of_sight(grid, &parent, &current) {
+                if let Some(cost) = segment_cost(grid, &parent, &current) {
+                    gscore[current.y as usize][current.x as usize] =
+                        gscore[parent.y as usize][parent.x as usize] + cost;
+                }
+            } else {
+                let mut best: Option<(f64, Point)> = None;
+                for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+                    if !closed[neighbor.y as usize][neighbor.x as usize] {
+                        continue;
+                    }
+                    let cost = match segment_cost(grid, &neighbor, &current) {
+                        Some(cost) => gscore[neighbor.y as usize][neighbor.x as usize] + cost,
+                        None => continue,
+                    };
+                    if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+                        best = Some((cost, neighbor));
+                    }
+                }
+                if let Some((cost, neighbor)) = best {
+                    came_from[current.y as usize][current.x as usize] = Some(neighbor);
+                    tracker.parent_updated(current, neighbor);
+                    gscore[current.y as usize][current.x as usize] = cost;
+                }
+            }
+        }
+
+        if current == *end {
+            break;
+        }
+
+        let parent = came_from[current.y as usize][current.x as usize].unwrap_or(current);
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            if !grid.is_passable(&neighbor) || closed[neighbor.y as usize][neighbor.x as usize] {
+                continue;
+            }
+
+            // priced as a clear line over the cheapest blocks, the real cost is settled on expansion
+            let tentative_gscore = gscore[parent.y as usize][parent.x as usize]
+                + Heuristic::Euclidean.estimate(grid, &parent, &neighbor);
+            if tentative_gscore < gscore[neighbor.y as usize][neighbor.x as usize] {
+                came_from[neighbor.y as usize][neighbor.x as usize] = Some(parent);
+                tracker.parent_updated(neighbor, parent);
+                gscore[neighbor.y as usize][neighbor.x as usize] = tentative_gscore;
+                open_set.push(FrontierNode::new(
+                    tentative_gscore + Heuristic::Euclidean.estimate(grid, &neighbor, end),
+                    neighbor,
+                ));
+                tracker.pushed(neighbor, open_set.len());
+            }
+        }
+    }
+
+    let waypoints = if gscore[end.y as usize][end.x as usize] == f64::INFINITY {
+        None
+    } else {
+        Some(reconstruct_path(&came_from, end))
+    };
+    SearchResult::with_waypoints(grid, waypoints, tracker.stats)
+}
@@ -0,0 +1,196 @@
+/*!
+ * Straight lines between block centres, used by the any-angle searches
+ * http://playtechs.blogspot.com/2007/03/raytracing-on-grid.html
+ *
+ * A line crosses every block its segment passes through the inside of. Lines running exactly
+ * through the corner where four blocks meet only touch the two blocks beside the corner, which
+ * block the line unless the grid allows cutting corners.
+ */
+use super::heuristic::Heuristic;
+use super::{Connectivity, GridMap, Point};
+
+pub struct Line {
+    /**
+     * Every block the line crosses, in order from the first point to the last
+     */
+    pub blocks: Vec<Point>,
+    /**
+     * The pairs of blocks beside each corner the line runs through
+     */
+    pub corners: Vec<(Point, Point)>,
+}
+
+pub fn trace(from: &Point, to: &Point) -> Line {
+    let dx = (to.x - from.x).abs();
+    let dy = (to.y - from.y).abs();
+    let step_x = (to.x - from.x).signum();
+    let step_y = (to.y - from.y).signum();
+
+    // the error compares how far the line is through the current column and row, in half blocks
+    let mut error = dx - dy;
+    let mut current = *from;
+    let mut remaining = dx + dy;
+    let mut line = Line {
+        blocks: vec![current],
+        corners: Vec::new(),
+    };
+    while remaining > 0 {
+        if error > 0 {
+            current.x += step_x;
+            error -= 2 * dy;
+            remaining -= 1;
+        } else if error < 0 {
+            current.y += step_y;
+            error += 2 * dx;
+            remaining -= 1;
+        } else {
+            line.corners.push((
+                Point::new(current.x + step_x, current.y),
+                Point::new(current.x, current.y + step_y),
+            ));
+            current = Point::new(current.x + step_x, current.y + step_y);
+            error += 2 * dx - 2 * dy;
+            remaining -= 2;
+        }
+        line.blocks.push(current);
+    }
+    line
+}
+
+/**
+ * Whether a unit can move in a straight line between the centres of the blocks
+ */
+pub fn line_of_sight(grid: &GridMap, from: &Point, to: &Point) -> bool {
+    let line = trace(from, to);
+    line.blocks.iter().all(|block| grid.is_passable(block))
+        && (grid.connectivity == Connectivity::EightWay
+            || line
+                .corners
+                .iter()
+                .all(|(a, b)| grid.is_passable(a) && grid.is_passable(b)))
+}
+
+/**
+ * The length of the line weighted by the average cost of the blocks it enters, a line to a
+ * neighbour costs the same as the step to it with the default diagonal cost. When diagonals cost
+ * less, the length is the octile distance instead so a line is never dearer than the grid path.
+ */
+pub fn segment_cost(grid: &GridMap, from: &Point, to: &Point) -> Option<f64> {
+    let line = trace(from, to);
+    let entered = &line.blocks[1..];
+    if entered.is_empty() {
+        return Some(0.0);
+    }
+
+    let mut total = 0.0;
+    for block in entered {
+        total += grid.get_cost(block)?;
+    }
+    let length = (((to.x - from.x).pow(2) + (to.y - from.y).pow(2)) as f64)
+        .sqrt()
+        .min(Heuristic::Octile.estimate(grid, from, to));
+    Some(length * total / entered.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map_file;
+
+    fn grid(map: &str, connectivity: Connectivity) -> GridMap {
+        let mut grid = map_file::from_text(map).expect("map is valid");
+        grid.set_connectivity(connectivity);
+        grid
+    }
+
+    #[test]
+    fn trace_crosses_every_block_the_segment_passes_through() {
+        let line = trace(&Point::new(0, 0), &Point::new(3, 1));
+        assert_eq!(
+            line.blocks,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(2, 1),
+                Point::new(3, 1)
+            ]
+        );
+        // halfway the line runs through the corner between the blocks it steps diagonally over
+        assert_eq!(line.corners, vec![(Point::new(2, 0), Point::new(1, 1))]);
+        assert!(trace(&Point::new(0, 0), &Point::new(3, 0))
+            .corners
+            .is_empty());
+
+        let diagonal = trace(&Point::new(2, 2), &Point::new(0, 0));
+        assert_eq!(
+            diagonal.blocks,
+            vec![Point::new(2, 2), Point::new(1, 1), Point::new(0, 0)]
+        );
+        assert_eq!(
+            diagonal.corners,
+            vec![
+                (Point::new(1, 2), Point::new(2, 1)),
+                (Point::new(0, 1), Point::new(1, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn obstacles_on_the_line_block_the_view() {
+        let map = "width 5\nheight 3\nmap\nS....\n..#..\n....E\n";
+        let grid = grid(map, Connectivity::EightWay);
+        assert!(!line_of_sight(&grid, &Point::new(0, 0), &Point::new(4, 2)));
+        assert!(!line_of_sight(&grid, &Point::new(0, 1), &Point::new(4, 1)));
+        assert!(line_of_sight(&grid, &Point::new(0, 0), &Point::new(4, 0)));
+        assert!(line_of_sight(&grid, &Point::new(0, 2), &Point::new(1, 0)));
+    }
+
+    #[test]
+    fn corners_block_the_view_without_corner_cutting() {
+        let map = "width 2\nheight 2\nmap\nS#\n.E\n";
+        let (from, to) = (Point::new(0, 0), Point::new(1, 1));
+        assert!(line_of_sight(
+            &grid(map, Connectivity::EightWay),
+            &from,
+            &to
+        ));
+        for connectivity in [Connectivity::EightWayNoCornerCutting, Connectivity::FourWay] {
+            assert!(!line_of_sight(&grid(map, connectivity), &from, &to));
+        }
+    }
+
+    #[test]
+    fn segments_cost_their_length_on_open_ground() {
+        let mut grid = grid(
+            "width 5\nheight 3\nmap\nS....\n.....\n....E\n",
+            Connectivity::EightWay,
+        );
+        let from = Point::new(0, 0);
+        assert_eq!(segment_cost(&grid, &from, &from), Some(0.0));
+        for to in [Point::new(1, 0), Point::new(1, 1), Point::new(4, 2)] {
+            let length = (((to.x - from.x).pow(2) + (to.y - from.y).pow(2)) as f64).sqrt();
+            assert_eq!(segment_cost(&grid, &from, &to), Some(length));
+        }
+        assert_eq!(
+            segment_cost(&grid, &from, &Point::new(1, 1)),
+            grid.step_cost(&from, &Point::new(1, 1))
+        );
+
+        // a cheap diagonal makes the line cost no more than the steps
+        grid.set_diagonal_cost(1.0);
+        assert_eq!(segment_cost(&grid, &from, &Point::new(2, 2)), Some(2.0));
+    }
+
+    #[test]
+    fn segments_cost_the_average_of_the_blocks_entered() {
+        let terrain = grid("width 4\nheight 1\nmap\nSmgE\n", Connectivity::FourWay);
+        let cost = segment_cost(&terrain, &Point::new(0, 0), &Point::new(3, 0));
+        // three blocks long at the average cost of mud, grass and the end
+        assert_eq!(cost, Some(4.0 + 2.0 + 1.0));
+        let blocked = grid("width 3\nheight 1\nmap\nS#E\n", Connectivity::FourWay);
+        assert_eq!(
+            segment_cost(&blocked, &Point::new(0, 0), &Point::new(2, 0)),
+            None
+        );
+    }
+}
@@ -1,54 +1,317 @@
 pub mod a_star;
 pub mod bellman_ford;
+pub mod bidirectional_a_star;
+pub mod bidirectional_breadth_first_search;
 pub mod breadth_first_search;
+pub mod comparison;
+pub mod d_star_lite;
 pub mod depth_first_search;
 pub mod dijkstra;
+pub mod events;
 pub mod greedy_best_first_search;
+pub mod heuristic;
+pub mod iterative_deepening_a_star;
+pub mod jump_point_search;
+pub mod lazy_theta_star;
+pub mod line_of_sight;
+pub mod theta_star;
 
 use crate::display::setup::MapCrowding;
-use rand::Rng;
+use crate::generation::cave::{self, CaveSettings};
+use crate::generation::dungeon::{self, DungeonSettings, Room};
+use crate::generation::maze::{self, MazeAlgorithm};
+use crate::generation::terrain::{self, TerrainSettings};
+use crate::generation::Generator;
+use crossterm::style::{style, Color, StyledContent, Stylize};
+use events::{NoopObserver, SearchEvent, SearchObserver};
+use heuristic::{Heuristic, WeightedHeuristic};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
 
 pub struct Pathfinder {
     pub grid: GridMap,
     pub start: Point,
     pub end: Point,
     pub algorithm: Algorithm,
+    pub heuristic: WeightedHeuristic,
 }
 
 impl Pathfinder {
+    /**
+     * Informed searches default to the unweighted heuristic matching the grid's connectivity, a
+     * weight set on the heuristic is only used by A Star
+     */
     pub fn new(grid: GridMap, start: Point, end: Point, algorithm: Algorithm) -> Pathfinder {
+        let heuristic = WeightedHeuristic::new(Heuristic::for_connectivity(grid.connectivity));
         Pathfinder {
             grid,
             start,
             end,
             algorithm,
+            heuristic,
         }
     }
 
-    pub fn run(self) {
-        match self.algorithm {
+    pub fn set_heuristic(mut self, heuristic: WeightedHeuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn run(&self) -> SearchResult {
+        self.run_observed(&mut NoopObserver)
+    }
+
+    /**
+     * Runs the search while reporting every step to the observer, finishing with the path if one was found
+     */
+    pub fn run_observed(&self, observer: &mut dyn SearchObserver) -> SearchResult {
+        let started = Instant::now();
+        // the weight is only an option of A Star, the other informed searches keep their guarantees
+        let unweighted = self.heuristic.set_weight(1.0);
+        let mut result = match self.algorithm {
             Algorithm::BreadthFirstSearch => {
-                breadth_first_search::run(&self.grid, &self.start, &self.end);
+                breadth_first_search::run(&self.grid, &self.start, &self.end, observer)
             }
             Algorithm::DepthFirstSearch => {
-                depth_first_search::run(&self.grid, &self.start, &self.end);
+                depth_first_search::run(&self.grid, &self.start, &self.end, observer)
             }
-            Algorithm::Dijkstra => {
-                dijkstra::run(self.grid, &self.start, &self.end);
+            Algorithm::Dijkstra => dijkstra::run(&self.grid, &self.start, &self.end, observer),
+            Algorithm::AStar => a_star::run(
+                &self.grid,
+                &self.start,
+                &self.end,
+                &self.heuristic,
+                observer,
+            ),
+            Algorithm::GreedyBestFirstSearch => greedy_best_first_search::run(
+                &self.grid,
+                &self.start,
+                &self.end,
+                &unweighted,
+                observer,
+            ),
+            Algorithm::BellmanFord => {
+                bellman_ford::run(&self.grid, &self.start, &self.end, observer)
             }
-            Algorithm::AStar => {
-                a_star::run(&self.grid, &self.start, &self.end);
+            Algorithm::JumpPointSearch if jump_point_search::supports(&self.grid) => {
+                jump_point_search::run(&self.grid, &self.start, &self.end, &unweighted, observer)
             }
-            Algorithm::GreedyBestFirstSearch => {
-                greedy_best_first_search::run(&self.grid, &self.start, &self.end);
+            Algorithm::BidirectionalBreadthFirstSearch => bidirectional_breadth_first_search::run(
+                &self.grid,
+                &self.start,
+                &self.end,
+                observer,
+            ),
+            Algorithm::BidirectionalAStar => {
+                bidirectional_a_star::run(&self.grid, &self.start, &self.end, &unweighted, observer)
             }
-            Algorithm::BellmanFord => {
-                bellman_ford::run(&self.grid, &self.start, &self.end);
+            Algorithm::ThetaStar => theta_star::run(&self.grid, &self.start, &self.end, observer),
+            Algorithm::LazyThetaStar => {
+                lazy_theta_star::run(&self.grid, &self.start, &self.end, observer)
             }
+            Algorithm::IterativeDeepeningAStar => iterative_deepening_a_star::run(
+                &self.grid,
+                &self.start,
+                &self.end,
+                &unweighted,
+                observer,
+            ),
+            Algorithm::DStarLite => {
+                d_star_lite::run(&self.grid, &self.start, &self.end, &unweighted, observer)
+            }
+            // Pruning is only safe on uniform 8-way grids, anywhere else the plain search gives the same path
+            Algorithm::JumpPointSearch => {
+                a_star::run(&self.grid, &self.start, &self.end, &unweighted, observer)
+            }
+        };
+        result.runtime = started.elapsed();
+
+        if result.found() {
+            observer.on_event(SearchEvent::PathFound(result.path.clone()));
+            if !result.waypoints.is_empty() {
+                observer.on_event(SearchEvent::Waypoints(result.waypoints.clone()));
+            }
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchStatus {
+    Found,
+    NotFound,
+}
+
+/**
+ * Counters an algorithm keeps while it searches
+ *
+ * Expanded: nodes taken off the frontier and processed
+ * Generated: nodes added to the frontier, including the start
+ * Iterations: times an iterative search started over, zero for searches that run once
+ * Re-expansions: expansions an iterative search repeats from its last iteration
+ */
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SearchStats {
+    pub nodes_expanded: i32,
+    pub nodes_generated: i32,
+    pub max_frontier_size: usize,
+    pub iterations: i32,
+    pub re_expansions: i32,
+}
+
+impl SearchStats {
+    pub fn new() -> SearchStats {
+        SearchStats::default()
+    }
+
+    pub fn record_frontier(&mut self, size: usize) {
+        self.max_frontier_size = self.max_frontier_size.max(size);
+    }
+}
+
+/**
+ * The outcome of a search, the path is ordered from start to end and is empty when no path was found
+ *
+ * Waypoints: the corners of an any-angle path, the path holds every block crossed on the way
+ * between them. Empty for searches that step between neighbouring blocks.
+ */
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub status: SearchStatus,
+    pub path: Vec<Point>,
+    pub waypoints: Vec<Point>,
+    pub total_cost: f64,
+    pub nodes_expanded: i32,
+    pub nodes_generated: i32,
+    pub max_frontier_size: usize,
+    pub iterations: i32,
+    pub re_expansions: i32,
+    pub runtime: Duration,
+}
+
+impl SearchResult {
+    pub fn new(grid: &GridMap, path: Option<Vec<Point>>, stats: SearchStats) -> SearchResult {
+        match path {
+            Some(path) => SearchResult {
+                status: SearchStatus::Found,
+                total_cost: path_cost(grid, &path),
+                path,
+                waypoints: Vec::new(),
+                nodes_expanded: stats.nodes_expanded,
+                nodes_generated: stats.nodes_generated,
+                max_frontier_size: stats.max_frontier_size,
+                iterations: stats.iterations,
+                re_expansions: stats.re_expansions,
+                runtime: Duration::ZERO,
+            },
+            None => SearchResult::without_path(SearchStatus::NotFound, stats),
+        }
+    }
+
+    pub fn without_path(status: SearchStatus, stats: SearchStats) -> SearchResult {
+        SearchResult {
+            status,
+            path: Vec::new(),
+            waypoints: Vec::new(),
+            total_cost: f64::INFINITY,
+            nodes_expanded: stats.nodes_expanded,
+            nodes_generated: stats.nodes_generated,
+            max_frontier_size: stats.max_frontier_size,
+            iterations: stats.iterations,
+            re_expansions: stats.re_expansions,
+            runtime: Duration::ZERO,
+        }
+    }
+
+    /**
+     * The result of an any-angle search, the cost is the summed cost of the straight segments
+     * between the waypoints
+     */
+    pub fn with_waypoints(
+        grid: &GridMap,
+        waypoints: Option<Vec<Point>>,
+        stats: SearchStats,
+    ) -> SearchResult {
+        let waypoints = match waypoints {
+            Some(waypoints) => waypoints,
+            None => return SearchResult::without_path(SearchStatus::NotFound, stats),
+        };
+
+        let mut path = vec![waypoints[0]];
+        let mut total_cost = 0.0;
+        for segment in waypoints.windows(2) {
+            path.extend(
+                line_of_sight::trace(&segment[0], &segment[1])
+                    .blocks
+                    .into_iter()
+                    .skip(1),
+            );
+            total_cost += line_of_sight::segment_cost(grid, &segment[0], &segment[1])
+                .expect("waypoints are in line of sight of each other");
         }
+        let mut result = SearchResult::new(grid, Some(path), stats);
+        result.total_cost = total_cost;
+        result.waypoints = waypoints;
+        result
+    }
+
+    pub fn found(&self) -> bool {
+        self.status == SearchStatus::Found
+    }
+
+    /**
+     * The number of steps in the path
+     */
+    pub fn path_length(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+}
+
+/**
+ * Walks the parent links back from the end point and returns the path ordered from start to end
+ */
+pub fn reconstruct_path(parent: &[Vec<Option<Point>>], end: &Point) -> Vec<Point> {
+    let mut path = vec![*end];
+    let mut current = *end;
+    while let Some(previous) = parent[current.y as usize][current.x as usize] {
+        path.push(previous);
+        current = previous;
     }
+    path.reverse();
+    path
 }
 
+/**
+ * Joins the halves of a bidirectional search at the meeting point, the parents from the end point
+ * towards the end instead of back to the start
+ */
+pub fn join_paths(
+    parent_from_start: &[Vec<Option<Point>>],
+    parent_from_end: &[Vec<Option<Point>>],
+    meeting: &Point,
+) -> Vec<Point> {
+    let mut path = reconstruct_path(parent_from_start, meeting);
+    let mut current = *meeting;
+    while let Some(next) = parent_from_end[current.y as usize][current.x as usize] {
+        path.push(next);
+        current = next;
+    }
+    path
+}
+
+/**
+ * The summed movement cost of every step in the path, the start block is free.
+ * Folded from zero since an empty float sum is negative zero, which prints as -0.
+ */
+pub fn path_cost(grid: &GridMap, path: &[Point]) -> f64 {
+    path.windows(2)
+        .filter_map(|step| grid.step_cost(&step[0], &step[1]))
+        .fold(0.0, |total, cost| total + cost)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Algorithm {
     BreadthFirstSearch,
     DepthFirstSearch,
@@ -56,44 +319,125 @@ pub enum Algorithm {
     AStar,
     GreedyBestFirstSearch,
     BellmanFord,
+    JumpPointSearch,
+    BidirectionalBreadthFirstSearch,
+    BidirectionalAStar,
+    ThetaStar,
+    LazyThetaStar,
+    IterativeDeepeningAStar,
+    DStarLite,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 13] = [
+        Algorithm::BreadthFirstSearch,
+        Algorithm::DepthFirstSearch,
+        Algorithm::Dijkstra,
+        Algorithm::AStar,
+        Algorithm::GreedyBestFirstSearch,
+        Algorithm::BellmanFord,
+        Algorithm::JumpPointSearch,
+        Algorithm::BidirectionalBreadthFirstSearch,
+        Algorithm::BidirectionalAStar,
+        Algorithm::ThetaStar,
+        Algorithm::LazyThetaStar,
+        Algorithm::IterativeDeepeningAStar,
+        Algorithm::DStarLite,
+    ];
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            Algorithm::BreadthFirstSearch => "Breadth First Search",
+            Algorithm::DepthFirstSearch => "Depth First Search",
+            Algorithm::Dijkstra => "Dijkstra",
+            Algorithm::AStar => "A Star",
+            Algorithm::GreedyBestFirstSearch => "Greedy Best First Search",
+            Algorithm::BellmanFord => "Bellman Ford",
+            Algorithm::JumpPointSearch => "Jump Point Search",
+            Algorithm::BidirectionalBreadthFirstSearch => "Bidirectional Breadth First Search",
+            Algorithm::BidirectionalAStar => "Bidirectional A Star",
+            Algorithm::ThetaStar => "Theta Star",
+            Algorithm::LazyThetaStar => "Lazy Theta Star",
+            Algorithm::IterativeDeepeningAStar => "Iterative Deepening A Star",
+            Algorithm::DStarLite => "D Star Lite",
+        }
+    }
+
+    /**
+     * The short name used on the command line
+     */
+    pub fn to_id(&self) -> &str {
+        match self {
+            Algorithm::BreadthFirstSearch => "bfs",
+            Algorithm::DepthFirstSearch => "dfs",
+            Algorithm::Dijkstra => "dijkstra",
+            Algorithm::AStar => "astar",
+            Algorithm::GreedyBestFirstSearch => "greedy",
+            Algorithm::BellmanFord => "bellman-ford",
+            Algorithm::JumpPointSearch => "jps",
+            Algorithm::BidirectionalBreadthFirstSearch => "bi-bfs",
+            Algorithm::BidirectionalAStar => "bi-astar",
+            Algorithm::ThetaStar => "theta",
+            Algorithm::LazyThetaStar => "lazy-theta",
+            Algorithm::IterativeDeepeningAStar => "ida",
+            Algorithm::DStarLite => "dstar-lite",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Algorithm> {
+        Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.to_id() == id)
+    }
+
+    /**
+     * Whether the algorithm is guided by a heuristic
+     */
+    pub fn is_informed(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::AStar
+                | Algorithm::GreedyBestFirstSearch
+                | Algorithm::JumpPointSearch
+                | Algorithm::BidirectionalAStar
+                | Algorithm::IterativeDeepeningAStar
+                | Algorithm::DStarLite
+        )
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GridBlock {
     Start,
     End,
     Obstacle,
     Path,
     Empty,
-}
-
-impl PartialEq for GridBlock {
-    fn eq(&self, other: &Self) -> bool {
-        match self {
-            GridBlock::Start => match other {
-                GridBlock::Start => true,
-                _ => false,
-            },
-            GridBlock::End => match other {
-                GridBlock::End => true,
-                _ => false,
-            },
-            GridBlock::Obstacle => match other {
-                GridBlock::Obstacle => true,
-                _ => false,
-            },
-            GridBlock::Path => match other {
-                GridBlock::Path => true,
-                _ => false,
-            },
-            GridBlock::Empty => match other {
-                GridBlock::Empty => true,
-                _ => false,
-            },
-        }
-    }
+    Frontier,
+    Visited,
+    JumpPoint,
+    EndFrontier,
+    EndVisited,
+    Meeting,
+    Waypoint,
+    Grass,
+    Sand,
+    Water,
+    Mud,
+    Hill,
+    DeepWater,
 }
 
 impl GridBlock {
+    pub const TERRAIN: [GridBlock; 6] = [
+        GridBlock::Grass,
+        GridBlock::Sand,
+        GridBlock::Water,
+        GridBlock::Mud,
+        GridBlock::Hill,
+        GridBlock::DeepWater,
+    ];
+
     pub fn to_visual_block(&self) -> &str {
         match self {
             GridBlock::Start => "▣",
@@ -101,6 +445,83 @@ impl GridBlock {
             GridBlock::Obstacle => "■",
             GridBlock::Path => "⊡",
             GridBlock::Empty => "•",
+            GridBlock::Frontier => "◇",
+            GridBlock::Visited => "∘",
+            GridBlock::JumpPoint => "◆",
+            GridBlock::EndFrontier => "◇",
+            GridBlock::EndVisited => "∘",
+            GridBlock::Meeting => "◎",
+            GridBlock::Waypoint => "◈",
+            GridBlock::Grass => "♣",
+            GridBlock::Sand => "∴",
+            GridBlock::Water => "≈",
+            GridBlock::Mud => "▒",
+            GridBlock::Hill => "∩",
+            GridBlock::DeepWater => "≋",
+        }
+    }
+
+    /**
+     * The character used for the block in map files, search overlays are saved as empty blocks
+     */
+    pub fn to_char(&self) -> char {
+        match self {
+            GridBlock::Start => 'S',
+            GridBlock::End => 'E',
+            GridBlock::Obstacle => '#',
+            GridBlock::Grass => 'g',
+            GridBlock::Sand => 's',
+            GridBlock::Water => 'w',
+            GridBlock::Mud => 'm',
+            GridBlock::Hill => 'h',
+            GridBlock::DeepWater => 'W',
+            GridBlock::Path
+            | GridBlock::Empty
+            | GridBlock::Frontier
+            | GridBlock::Visited
+            | GridBlock::JumpPoint
+            | GridBlock::EndFrontier
+            | GridBlock::EndVisited
+            | GridBlock::Meeting
+            | GridBlock::Waypoint => '.',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<GridBlock> {
+        match c {
+            'S' => Some(GridBlock::Start),
+            'E' => Some(GridBlock::End),
+            '#' => Some(GridBlock::Obstacle),
+            'g' => Some(GridBlock::Grass),
+            's' => Some(GridBlock::Sand),
+            'w' => Some(GridBlock::Water),
+            'm' => Some(GridBlock::Mud),
+            'h' => Some(GridBlock::Hill),
+            'W' => Some(GridBlock::DeepWater),
+            '.' => Some(GridBlock::Empty),
+            _ => None,
+        }
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            GridBlock::Grass => Some(Color::Green),
+            GridBlock::Sand => Some(Color::Yellow),
+            GridBlock::Water => Some(Color::Blue),
+            GridBlock::Mud => Some(Color::DarkYellow),
+            GridBlock::Hill => Some(Color::DarkGrey),
+            GridBlock::DeepWater => Some(Color::DarkBlue),
+            GridBlock::JumpPoint => Some(Color::Magenta),
+            GridBlock::EndFrontier | GridBlock::EndVisited => Some(Color::Cyan),
+            GridBlock::Meeting | GridBlock::Waypoint => Some(Color::Red),
+            _ => None,
+        }
+    }
+
+    pub fn to_styled_block(&self) -> StyledContent<&str> {
+        match self.color() {
+            Some(color) => self.to_visual_block().with(color),
+            None => style(self.to_visual_block()),
         }
     }
 
@@ -111,18 +532,40 @@ impl GridBlock {
             GridBlock::Obstacle => "Obstacle",
             GridBlock::Path => "Path",
             GridBlock::Empty => "Empty",
+            GridBlock::Frontier => "Frontier",
+            GridBlock::Visited => "Visited",
+            GridBlock::JumpPoint => "Jump Point",
+            GridBlock::EndFrontier => "Frontier From End",
+            GridBlock::EndVisited => "Visited From End",
+            GridBlock::Meeting => "Meeting Point",
+            GridBlock::Waypoint => "Waypoint",
+            GridBlock::Grass => "Grass",
+            GridBlock::Sand => "Sand",
+            GridBlock::Water => "Water",
+            GridBlock::Mud => "Mud",
+            GridBlock::Hill => "Hill",
+            GridBlock::DeepWater => "Deep Water",
         }
     }
 
-    pub fn to_block(&self) -> GridBlock {
+    /**
+     * The cost of moving onto this block, or `None` if the block can not be entered
+     */
+    pub fn movement_cost(&self) -> Option<f64> {
         match self {
-            GridBlock::Start => GridBlock::Start,
-            GridBlock::End => GridBlock::End,
-            GridBlock::Obstacle => GridBlock::Obstacle,
-            GridBlock::Path => GridBlock::Path,
-            GridBlock::Empty => GridBlock::Empty,
+            GridBlock::Obstacle | GridBlock::DeepWater => None,
+            GridBlock::Grass => Some(2.0),
+            GridBlock::Sand => Some(3.0),
+            GridBlock::Mud => Some(4.0),
+            GridBlock::Hill => Some(5.0),
+            GridBlock::Water => Some(6.0),
+            _ => Some(1.0),
         }
     }
+
+    pub fn to_block(&self) -> GridBlock {
+        *self
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -143,6 +586,42 @@ impl Point {
     }
 }
 
+/**
+ * An entry in a priority queue frontier, ordered so that `BinaryHeap` pops the lowest priority first
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct FrontierNode {
+    pub priority: f64,
+    pub point: Point,
+}
+
+impl FrontierNode {
+    pub fn new(priority: f64, point: Point) -> FrontierNode {
+        FrontierNode { priority, point }
+    }
+}
+
+impl PartialEq for FrontierNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FrontierNode {}
+
+impl PartialOrd for FrontierNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FrontierNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority)
+    }
+}
+
+#[derive(Clone)]
 pub struct GridElement {
     pub point: Point,
     pub grid: GridBlock,
@@ -154,6 +633,7 @@ impl GridElement {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct GridSize {
     pub width: i32,
     pub height: i32,
@@ -165,12 +645,66 @@ impl GridSize {
     }
 }
 
+/**
+ * Which neighbours can be reached in a single step
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    FourWay,
+    EightWay,
+    EightWayNoCornerCutting,
+}
+
+impl Connectivity {
+    pub const ALL: [Connectivity; 3] = [
+        Connectivity::FourWay,
+        Connectivity::EightWay,
+        Connectivity::EightWayNoCornerCutting,
+    ];
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            Connectivity::FourWay => "4-way",
+            Connectivity::EightWay => "8-way",
+            Connectivity::EightWayNoCornerCutting => "8-way without corner cutting",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Connectivity> {
+        Connectivity::ALL
+            .into_iter()
+            .find(|connectivity| connectivity.to_name() == name)
+    }
+
+    /**
+     * The short name used on the command line
+     */
+    pub fn to_id(&self) -> &str {
+        match self {
+            Connectivity::FourWay => "4-way",
+            Connectivity::EightWay => "8-way",
+            Connectivity::EightWayNoCornerCutting => "8-way-no-corner-cutting",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Connectivity> {
+        Connectivity::ALL
+            .into_iter()
+            .find(|connectivity| connectivity.to_id() == id)
+    }
+}
+
+#[derive(Clone)]
 pub struct GridMap {
     pub grid: Vec<Vec<GridElement>>,
     pub size: GridSize,
     pub full_size: i32,
     pub start: Point,
     pub end: Point,
+    pub connectivity: Connectivity,
+    pub diagonal_cost: f64,
+    pub seed: Option<u64>,
+    pub generator: Option<Generator>,
 }
 
 impl GridMap {
@@ -190,6 +724,10 @@ impl GridMap {
             full_size,
             start: Point::new(0, 0),
             end: Point::new(0, 0),
+            connectivity: Connectivity::FourWay,
+            diagonal_cost: std::f64::consts::SQRT_2,
+            seed: None,
+            generator: None,
         }
     }
 
@@ -203,48 +741,76 @@ impl GridMap {
         points
     }
 
-    pub fn get_surrounding_blocks(&self, point: &Point) -> Vec<(GridBlock, Point)> {
-        let mut blocks: Vec<(GridBlock, Point)> = Vec::new();
+    pub fn set_connectivity(&mut self, connectivity: Connectivity) {
+        self.connectivity = connectivity;
+    }
 
-        if point.x > 0 {
-            blocks.push((
-                self.grid[point.y as usize][point.x as usize - 1]
-                    .grid
-                    .to_block(),
-                Point::new(point.x - 1, point.y),
-            ));
-        }
+    pub fn set_diagonal_cost(&mut self, diagonal_cost: f64) {
+        self.diagonal_cost = diagonal_cost;
+    }
 
-        if point.x < self.size.width - 1 {
-            blocks.push((
-                self.grid[point.y as usize][point.x as usize + 1]
-                    .grid
-                    .to_block(),
-                Point::new(point.x + 1, point.y),
-            ));
-        }
+    /**
+     * The seed the map was generated from, kept so the map can be generated again
+     */
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.size.width && point.y < self.size.height
+    }
+
+    pub fn is_passable(&self, point: &Point) -> bool {
+        self.contains(point) && self.get_cost(point).is_some()
+    }
 
-        if point.y > 0 {
-            blocks.push((
-                self.grid[point.y as usize - 1][point.x as usize]
-                    .grid
-                    .to_block(),
-                Point::new(point.x, point.y - 1),
-            ));
+    /**
+     * The neighbours of the point allowed by the grid's connectivity, orthogonal neighbours come first
+     *
+     * Obstacles are included, diagonals that would cut the corner of an obstacle are not when corner cutting is disabled
+     */
+    pub fn get_surrounding_blocks(&self, point: &Point) -> Vec<(GridBlock, Point)> {
+        let mut offsets = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+        if self.connectivity != Connectivity::FourWay {
+            offsets.extend([(-1, -1), (1, -1), (-1, 1), (1, 1)]);
         }
 
-        if point.y < self.size.height - 1 {
-            blocks.push((
-                self.grid[point.y as usize + 1][point.x as usize]
-                    .grid
-                    .to_block(),
-                Point::new(point.x, point.y + 1),
-            ));
+        let mut blocks: Vec<(GridBlock, Point)> = Vec::new();
+        for (dx, dy) in offsets {
+            let neighbor = Point::new(point.x + dx, point.y + dy);
+            if !self.contains(&neighbor) {
+                continue;
+            }
+            if dx != 0
+                && dy != 0
+                && self.connectivity == Connectivity::EightWayNoCornerCutting
+                && (!self.is_passable(&Point::new(point.x + dx, point.y))
+                    || !self.is_passable(&Point::new(point.x, point.y + dy)))
+            {
+                continue;
+            }
+            blocks.push((self.get_block(&neighbor).to_block(), neighbor));
         }
 
         blocks
     }
 
+    /**
+     * The cost of stepping between two neighbouring points, diagonal steps are scaled by the diagonal cost
+     */
+    pub fn step_cost(&self, from: &Point, to: &Point) -> Option<f64> {
+        let cost = self.get_cost(to)?;
+        if from.x != to.x && from.y != to.y {
+            Some(cost * self.diagonal_cost)
+        } else {
+            Some(cost)
+        }
+    }
+
+    pub fn get_cost(&self, point: &Point) -> Option<f64> {
+        self.get_block(point).movement_cost()
+    }
+
     pub fn get_block(&self, point: &Point) -> &GridBlock {
         &self.grid[point.y as usize][point.x as usize].grid
     }
@@ -261,6 +827,29 @@ impl GridMap {
         self.end = Point::new(point.x, point.y);
     }
 
+    /**
+     * Moves the start and end blocks to the given points, replacing whatever is under them
+     */
+    pub fn place_endpoints(&mut self, start: &Point, end: &Point) {
+        for point in [self.start, self.end] {
+            if matches!(self.get_block(&point), GridBlock::Start | GridBlock::End) {
+                self.set_block(&point, &GridBlock::Empty);
+            }
+        }
+        self.set_block(start, &GridBlock::Start);
+        self.set_start(start);
+        self.set_block(end, &GridBlock::End);
+        self.set_end(end);
+    }
+
+    pub fn has_start(&self) -> bool {
+        self.get_block(&self.start) == &GridBlock::Start
+    }
+
+    pub fn has_end(&self) -> bool {
+        self.get_block(&self.end) == &GridBlock::End
+    }
+
     /**
      * Populates the current object's grid with obstacles in a semi-random fashion. This method performs two primary actions:
      *
@@ -278,12 +867,12 @@ impl GridMap {
      *
      * Preconditions:
      * - The grid (`self.grid`), its dimensions (`self.size.width` and `self.size.height`), and the obstacle enum (`GridBlock::Obstacle`) are defined.
-     * - The `rand::thread_rng().gen_range()` function is used for random number generation.
+     * - The `gen_range()` function of the random number generator is used for random number generation.
      *
      * Postconditions:
      * - The grid will contain a mix of rectangular obstacles and randomly placed obstacles, subject to the limits of `n` obstacles and the `p` percent chance for any unmarked cell.
      */
-    pub fn generate_obstacles(&mut self, crowding: MapCrowding) {
+    pub fn generate_obstacles(&mut self, crowding: MapCrowding, rng: &mut impl Rng) {
         let min = crowding.min_obstacle_size();
         let max = crowding.max_obstacle_size();
         let diff = max - min;
@@ -296,11 +885,11 @@ impl GridMap {
             let mut x2: i32;
             let mut y2: i32;
             for _ in 0..retries {
-                x1 = rand::thread_rng().gen_range(0..self.size.width);
-                y1 = rand::thread_rng().gen_range(0..self.size.height);
+                x1 = rng.gen_range(0..self.size.width);
+                y1 = rng.gen_range(0..self.size.height);
 
-                x2 = x1 + min + rand::thread_rng().gen_range(0..diff);
-                y2 = y1 + min + rand::thread_rng().gen_range(0..diff);
+                x2 = x1 + min + rng.gen_range(0..diff);
+                y2 = y1 + min + rng.gen_range(0..diff);
 
                 if x2 < self.size.width && y2 < self.size.height {
                     let mut tmp = Vec::new();
@@ -331,32 +920,536 @@ impl GridMap {
                 if self.grid[j as usize][i as usize].grid == GridBlock::Obstacle {
                     continue;
                 }
-                if rand::thread_rng().gen_range(0.0..1.0) < crowding.convert_chance() {
+                if rng.gen_range(0.0..1.0) < crowding.convert_chance() {
                     self.grid[j as usize][i as usize].grid = GridBlock::Obstacle;
                 }
             }
         }
     }
 
+    /**
+     * Replaces the grid with a maze, each wall between two passages is knocked out with the loop chance
+     */
+    pub fn generate_maze(
+        &mut self,
+        algorithm: MazeAlgorithm,
+        loop_chance: f64,
+        rng: &mut impl Rng,
+    ) {
+        maze::generate(self, algorithm, rng);
+        if loop_chance > 0.0 {
+            maze::add_loops(self, loop_chance, rng);
+        }
+    }
+
+    /**
+     * Replaces the grid with cellular automaton caves
+     */
+    pub fn generate_cave(&mut self, settings: &CaveSettings, rng: &mut impl Rng) {
+        cave::generate(self, settings, rng);
+    }
+
+    /**
+     * Replaces the grid with rooms joined by corridors and returns the rooms
+     */
+    pub fn generate_dungeon(
+        &mut self,
+        settings: &DungeonSettings,
+        rng: &mut impl Rng,
+    ) -> Vec<Room> {
+        dungeon::generate(self, settings, rng)
+    }
+
+    /**
+     * Replaces the grid with weighted terrain, hills cost more to cross and rivers can not be crossed
+     */
+    pub fn generate_terrain(&mut self, settings: &TerrainSettings, rng: &mut impl Rng) {
+        terrain::generate(self, settings, rng);
+    }
+
+    /**
+     * Runs the generator from a random number generator seeded with the given seed and remembers both,
+     * the same generator and seed always give the same map, on any platform
+     */
+    pub fn generate(&mut self, generator: &Generator, seed: u64) {
+        generator.generate(self, &mut ChaCha8Rng::seed_from_u64(seed));
+        self.seed = Some(seed);
+        self.generator = Some(*generator);
+    }
+
+    /**
+     * Forgets the generator and seed once the map is changed by hand, they would no longer give the same map
+     */
+    pub fn clear_generation(&mut self) {
+        self.seed = None;
+        self.generator = None;
+    }
+
     pub fn render_with_selector(&self, point: &Point, selector: &GridBlock) {
         for (y, row) in self.grid.iter().enumerate() {
             for (x, element) in row.iter().enumerate() {
                 if point.x == x as i32 && point.y == y as i32 {
-                    print!(" {} ", selector.to_visual_block());
+                    print!(" {} ", selector.to_styled_block());
                 } else {
-                    print!(" {} ", element.grid.to_visual_block());
+                    print!(" {} ", element.grid.to_styled_block());
                 }
             }
             println!();
         }
     }
 
+    /**
+     * A single row as it is rendered, used to draw grids next to each other
+     */
+    pub fn render_row(&self, y: usize) -> String {
+        self.grid[y]
+            .iter()
+            .map(|element| format!(" {} ", element.grid.to_styled_block()))
+            .collect()
+    }
+
     pub fn render(&self) {
-        for row in &self.grid {
-            for element in row {
-                print!(" {} ", element.grid.to_visual_block());
+        for y in 0..self.grid.len() {
+            println!("{}", self.render_row(y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::events::EventRecorder;
+    use super::*;
+
+    /**
+     * Builds a grid from rows of blocks, `S` and `E` mark the start and end, `#` an obstacle and
+     * `g`, `s`, `w` and `m` grass, sand, water and mud
+     */
+    fn grid(rows: &[&str], connectivity: Connectivity) -> GridMap {
+        let mut grid = GridMap::new(GridSize::new(rows[0].len() as i32, rows.len() as i32));
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let point = Point::new(x as i32, y as i32);
+                let block = match c {
+                    'S' => GridBlock::Start,
+                    'E' => GridBlock::End,
+                    '#' => GridBlock::Obstacle,
+                    'g' => GridBlock::Grass,
+                    's' => GridBlock::Sand,
+                    'w' => GridBlock::Water,
+                    'm' => GridBlock::Mud,
+                    _ => GridBlock::Empty,
+                };
+                match block {
+                    GridBlock::Start => grid.set_start(&point),
+                    GridBlock::End => grid.set_end(&point),
+                    _ => {}
+                }
+                grid.set_block(&point, &block);
             }
-            println!();
+        }
+        grid.set_connectivity(connectivity);
+        grid
+    }
+
+    fn run(grid: &GridMap, algorithm: Algorithm) -> SearchResult {
+        Pathfinder::new(grid.clone(), grid.start, grid.end, algorithm).run()
+    }
+
+    /**
+     * Checks the path runs from the start to the end in steps the grid allows, at the cost reported
+     */
+    fn assert_valid_path(grid: &GridMap, result: &SearchResult, name: &str) {
+        assert!(result.found(), "{} found no path", name);
+        assert_eq!(result.path.first(), Some(&grid.start), "{}", name);
+        assert_eq!(result.path.last(), Some(&grid.end), "{}", name);
+        for step in result.path.windows(2) {
+            assert!(
+                grid.get_surrounding_blocks(&step[0])
+                    .iter()
+                    .any(|(_, point)| *point == step[1]),
+                "{} steps from {:?} to {:?}",
+                name,
+                step[0],
+                step[1]
+            );
+        }
+        assert_eq!(result.total_cost, path_cost(grid, &result.path), "{}", name);
+    }
+
+    const OPEN: [&str; 5] = ["S.......", "........", "........", "........", ".......E"];
+    const WALLED: [&str; 6] = [
+        "S..#....", ".#.#.##.", ".#...#..", ".####.#.", "......#.", "###.#..E",
+    ];
+    const TERRAIN: [&str; 5] = ["S.ggg...", ".#sss#..", ".#mmm#..", ".#ggg#w.", "...wwwwE"];
+
+    #[test]
+    fn reconstruct_path_follows_parents_from_the_start() {
+        let mut parent = vec![vec![None; 3]; 2];
+        parent[0][1] = Some(Point::new(0, 0));
+        parent[1][1] = Some(Point::new(1, 0));
+        parent[1][2] = Some(Point::new(1, 1));
+
+        let path = reconstruct_path(&parent, &Point::new(2, 1));
+        assert_eq!(
+            path,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(1, 1),
+                Point::new(2, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn search_result_keeps_the_path_cost_and_stats() {
+        let grid = grid(&["S.g", "..E"], Connectivity::FourWay);
+        let path = vec![
+            Point::new(0, 0),
+            Point::new(1, 0),
+            Point::new(2, 0),
+            Point::new(2, 1),
+        ];
+        let stats = SearchStats {
+            nodes_expanded: 4,
+            nodes_generated: 6,
+            max_frontier_size: 3,
+            iterations: 0,
+            re_expansions: 0,
+        };
+
+        let result = SearchResult::new(&grid, Some(path.clone()), stats);
+        assert!(result.found());
+        assert_eq!(result.path, path);
+        assert_eq!(result.path_length(), 3);
+        assert_eq!(result.total_cost, 4.0);
+        assert_eq!(result.nodes_expanded, 4);
+        assert_eq!(result.nodes_generated, 6);
+        assert_eq!(result.max_frontier_size, 3);
+
+        let missing = SearchResult::new(&grid, None, stats);
+        assert_eq!(missing.status, SearchStatus::NotFound);
+        assert!(missing.path.is_empty());
+        assert_eq!(missing.total_cost, f64::INFINITY);
+        assert_eq!(missing.nodes_expanded, 4);
+    }
+
+    #[test]
+    fn path_to_the_start_costs_positive_zero() {
+        let grid = grid(&["S.", ".E"], Connectivity::FourWay);
+        let cost = path_cost(&grid, &[Point::new(0, 0)]);
+        assert_eq!(cost, 0.0);
+        assert!(cost.is_sign_positive());
+        assert!(path_cost(&grid, &[]).is_sign_positive());
+    }
+
+    #[test]
+    fn optimal_searches_match_dijkstra() {
+        for rows in [&OPEN[..], &WALLED[..], &TERRAIN[..]] {
+            for connectivity in Connectivity::ALL {
+                let grid = grid(rows, connectivity);
+                let reference = run(&grid, Algorithm::Dijkstra);
+                assert!(reference.found());
+                for algorithm in [
+                    Algorithm::AStar,
+                    Algorithm::BellmanFord,
+                    Algorithm::JumpPointSearch,
+                    Algorithm::BidirectionalAStar,
+                    Algorithm::IterativeDeepeningAStar,
+                    Algorithm::DStarLite,
+                ] {
+                    let result = run(&grid, algorithm);
+                    assert!(
+                        (result.total_cost - reference.total_cost).abs() < 1e-9,
+                        "{} on {:?} {:?}: {} instead of {}",
+                        algorithm.to_name(),
+                        rows,
+                        connectivity,
+                        result.total_cost,
+                        reference.total_cost
+                    );
+                    assert_eq!(result.path.first(), Some(&grid.start));
+                    assert_eq!(result.path.last(), Some(&grid.end));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_search_returns_a_walkable_path() {
+        for rows in [&OPEN[..], &WALLED[..], &TERRAIN[..]] {
+            for connectivity in Connectivity::ALL {
+                let grid = grid(rows, connectivity);
+                for algorithm in [
+                    Algorithm::BreadthFirstSearch,
+                    Algorithm::DepthFirstSearch,
+                    Algorithm::GreedyBestFirstSearch,
+                    Algorithm::BellmanFord,
+                ] {
+                    let name = format!("{} on {:?} {:?}", algorithm.to_name(), rows, connectivity);
+                    assert_valid_path(&grid, &run(&grid, algorithm), &name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn breadth_first_search_takes_the_fewest_steps() {
+        for rows in [&OPEN[..], &WALLED[..]] {
+            for connectivity in Connectivity::ALL {
+                // with every step costing one the cheapest path is also the shortest
+                let mut grid = grid(rows, connectivity);
+                grid.set_diagonal_cost(1.0);
+                let steps = run(&grid, Algorithm::Dijkstra).total_cost as usize;
+                assert_eq!(
+                    run(&grid, Algorithm::BreadthFirstSearch).path_length(),
+                    steps
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bellman_ford_goes_around_costly_terrain() {
+        let grid = grid(&["S.m.E", "#...#"], Connectivity::FourWay);
+        let bellman_ford = run(&grid, Algorithm::BellmanFord);
+        assert_eq!(bellman_ford.total_cost, 6.0);
+        assert_eq!(bellman_ford.path_length(), 6);
+        assert_eq!(run(&grid, Algorithm::BreadthFirstSearch).path_length(), 4);
+    }
+
+    #[test]
+    fn bidirectional_breadth_first_search_takes_the_fewest_steps() {
+        for rows in [&OPEN[..], &WALLED[..]] {
+            for connectivity in Connectivity::ALL {
+                let grid = grid(rows, connectivity);
+                let bfs = run(&grid, Algorithm::BreadthFirstSearch);
+                let bidirectional = run(&grid, Algorithm::BidirectionalBreadthFirstSearch);
+                assert_eq!(bidirectional.path_length(), bfs.path_length());
+                assert_eq!(bidirectional.path.first(), Some(&grid.start));
+                assert_eq!(bidirectional.path.last(), Some(&grid.end));
+            }
+        }
+    }
+
+    #[test]
+    fn weight_is_ignored_by_the_other_informed_searches() {
+        let grid = grid(&TERRAIN, Connectivity::EightWay);
+        let heuristic = WeightedHeuristic::new(Heuristic::Octile).set_weight(5.0);
+        let reference = run(&grid, Algorithm::Dijkstra);
+        for algorithm in [
+            Algorithm::BidirectionalAStar,
+            Algorithm::IterativeDeepeningAStar,
+            Algorithm::DStarLite,
+        ] {
+            let result = Pathfinder::new(grid.clone(), grid.start, grid.end, algorithm)
+                .set_heuristic(heuristic)
+                .run();
+            assert!(
+                (result.total_cost - reference.total_cost).abs() < 1e-9,
+                "{}",
+                algorithm.to_name()
+            );
+        }
+
+        // the stopping rule is only sound with the plain heuristic, whoever calls it
+        let bidirectional =
+            bidirectional_a_star::run(&grid, &grid.start, &grid.end, &heuristic, &mut NoopObserver);
+        assert!((bidirectional.total_cost - reference.total_cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn iterative_deepening_raises_the_threshold_until_the_end_is_reached() {
+        let grid = grid(&TERRAIN, Connectivity::FourWay);
+        let result = run(&grid, Algorithm::IterativeDeepeningAStar);
+        assert!(result.iterations > 1);
+        assert!(result.re_expansions > 0);
+        assert_eq!(run(&grid, Algorithm::AStar).iterations, 0);
+    }
+
+    #[test]
+    fn iterative_deepening_keeps_only_the_path() {
+        let grid = grid(&TERRAIN, Connectivity::FourWay);
+        let result = run(&grid, Algorithm::IterativeDeepeningAStar);
+        // no step costs less than one, so no path tried has more steps than the cost found
+        assert!(result.max_frontier_size as f64 <= result.total_cost + 2.0);
+    }
+
+    #[test]
+    fn iterative_deepening_counts_every_repeated_expansion() {
+        for connectivity in Connectivity::ALL {
+            let grid = grid(&TERRAIN, connectivity);
+            let mut recorder = EventRecorder::new();
+            let result = Pathfinder::new(
+                grid.clone(),
+                grid.start,
+                grid.end,
+                Algorithm::IterativeDeepeningAStar,
+            )
+            .run_observed(&mut recorder);
+            let mut expanded = Vec::new();
+            for event in recorder.events {
+                if let SearchEvent::Expanded(point) = event {
+                    if !expanded.contains(&point) {
+                        expanded.push(point);
+                    }
+                }
+            }
+            assert_eq!(
+                result.re_expansions,
+                result.nodes_expanded - expanded.len() as i32,
+                "{:?}",
+                connectivity
+            );
+        }
+    }
+
+    #[test]
+    fn iterative_deepening_rules_out_a_walled_off_end() {
+        let mut rows = vec![".".repeat(12); 12];
+        rows[0] = format!("S{}", ".".repeat(11));
+        rows[9] = format!("{}###", ".".repeat(9));
+        rows[10] = format!("{}#..", ".".repeat(9));
+        rows[11] = format!("{}#.E", ".".repeat(9));
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        for connectivity in Connectivity::ALL {
+            let result = run(
+                &grid(&rows, connectivity),
+                Algorithm::IterativeDeepeningAStar,
+            );
+            assert_eq!(result.status, SearchStatus::NotFound);
+            assert_eq!(result.nodes_expanded, 0);
+        }
+    }
+
+    #[test]
+    fn iterative_deepening_cuts_off_transpositions_on_open_grids() {
+        let mut rows = vec![format!("{}#{}", ".".repeat(20), ".".repeat(19)); 36];
+        rows.extend(vec![".".repeat(40); 4]);
+        rows[0].replace_range(0..1, "S");
+        rows[39].replace_range(39..40, "E");
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        let grid = grid(&rows, Connectivity::EightWay);
+        let reference = run(&grid, Algorithm::Dijkstra);
+        let result = run(&grid, Algorithm::IterativeDeepeningAStar);
+        assert!((result.total_cost - reference.total_cost).abs() < 1e-9);
+        // without the table every way around the wall would be tried again in each iteration
+        assert!(result.nodes_expanded < 100 * reference.nodes_expanded);
+    }
+
+    #[test]
+    fn any_angle_paths_are_never_longer_than_grid_paths() {
+        for rows in [&OPEN[..], &WALLED[..], &TERRAIN[..]] {
+            for connectivity in Connectivity::ALL {
+                let grid = grid(rows, connectivity);
+                let reference = run(&grid, Algorithm::Dijkstra);
+                for algorithm in [Algorithm::ThetaStar, Algorithm::LazyThetaStar] {
+                    let result = run(&grid, algorithm);
+                    let name = format!("{} on {:?} {:?}", algorithm.to_name(), rows, connectivity);
+                    assert!(
+                        result.total_cost <= reference.total_cost + 1e-9,
+                        "{}: {} against {}",
+                        name,
+                        result.total_cost,
+                        reference.total_cost
+                    );
+                    assert_eq!(result.waypoints.first(), Some(&grid.start), "{}", name);
+                    assert_eq!(result.waypoints.last(), Some(&grid.end), "{}", name);
+                    for segment in result.waypoints.windows(2) {
+                        assert!(
+                            line_of_sight::line_of_sight(&grid, &segment[0], &segment[1]),
+                            "{}",
+                            name
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn any_angle_paths_cut_across_open_ground() {
+        let grid = grid(&OPEN, Connectivity::EightWay);
+        let straight = (7.0f64 * 7.0 + 4.0 * 4.0).sqrt();
+        for algorithm in [Algorithm::ThetaStar, Algorithm::LazyThetaStar] {
+            let result = run(&grid, algorithm);
+            assert_eq!(result.waypoints, vec![grid.start, grid.end]);
+            assert!((result.total_cost - straight).abs() < 1e-9);
+            assert!(result.total_cost < run(&grid, Algorithm::Dijkstra).total_cost);
+        }
+    }
+
+    #[test]
+    fn jump_point_search_prunes_on_open_grids() {
+        let grid = grid(&OPEN, Connectivity::EightWay);
+        assert!(jump_point_search::supports(&grid));
+        let jps = run(&grid, Algorithm::JumpPointSearch);
+        let a_star = run(&grid, Algorithm::AStar);
+        assert_eq!(jps.total_cost, a_star.total_cost);
+        assert!(jps.nodes_generated <= a_star.nodes_generated);
+    }
+
+    #[test]
+    fn jump_point_search_falls_back_when_diagonals_break_its_pruning() {
+        for diagonal_cost in [0.5, 2.0, 3.0] {
+            let mut grid = grid(&WALLED, Connectivity::EightWay);
+            grid.set_diagonal_cost(diagonal_cost);
+            assert!(!jump_point_search::supports(&grid));
+            let reference = run(&grid, Algorithm::Dijkstra);
+            let result = run(&grid, Algorithm::JumpPointSearch);
+            assert!(
+                (result.total_cost - reference.total_cost).abs() < 1e-9,
+                "{}",
+                diagonal_cost
+            );
+        }
+    }
+
+    #[test]
+    fn cheap_diagonals_keep_the_straight_line_below_the_grid_path() {
+        for diagonal_cost in [1.0, 1.2] {
+            let mut grid = grid(&OPEN, Connectivity::EightWay);
+            grid.set_diagonal_cost(diagonal_cost);
+            let reference = run(&grid, Algorithm::Dijkstra);
+            let euclidean = Pathfinder::new(grid.clone(), grid.start, grid.end, Algorithm::AStar)
+                .set_heuristic(WeightedHeuristic::new(Heuristic::Euclidean))
+                .run();
+            assert!((euclidean.total_cost - reference.total_cost).abs() < 1e-9);
+            for algorithm in [Algorithm::ThetaStar, Algorithm::LazyThetaStar] {
+                let result = run(&grid, algorithm);
+                assert!(
+                    result.total_cost <= reference.total_cost + 1e-9,
+                    "{} with {}",
+                    algorithm.to_name(),
+                    diagonal_cost
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn diagonals_can_not_cut_corners_without_corner_cutting() {
+        let rows = ["S#", "#E"];
+        let diagonal = run(&grid(&rows, Connectivity::EightWay), Algorithm::Dijkstra);
+        assert_eq!(diagonal.total_cost, std::f64::consts::SQRT_2);
+        for connectivity in [Connectivity::FourWay, Connectivity::EightWayNoCornerCutting] {
+            let result = run(&grid(&rows, connectivity), Algorithm::Dijkstra);
+            assert_eq!(result.status, SearchStatus::NotFound);
+        }
+    }
+
+    #[test]
+    fn unreachable_end_is_not_found() {
+        let grid = grid(&["S.#..", "..#.E"], Connectivity::EightWay);
+        for algorithm in Algorithm::ALL {
+            let result = run(&grid, algorithm);
+            assert_eq!(
+                result.status,
+                SearchStatus::NotFound,
+                "{}",
+                algorithm.to_name()
+            );
+            assert!(result.path.is_empty());
         }
     }
 }
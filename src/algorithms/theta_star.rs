@@ -0,0 +1,80 @@
+//https://en.wikipedia.org/wiki/Theta*
+
+use super::events::{SearchObserver, SearchTracker};
+use super::heuristic::Heuristic;
+use super::line_of_sight::{line_of_sight, segment_cost};
+use super::{reconstruct_path, FrontierNode, Point, SearchResult};
+use crate::algorithms::GridMap;
+use std::collections::BinaryHeap;
+
+pub fn run(
+    grid: &GridMap,
+    start: &Point,
+    end: &Point,
+    observer: &mut dyn SearchObserver,
+) -> SearchResult {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut closed = vec![vec![false; width]; height];
+    let mut gscore = vec![vec![f64::INFINITY; width]; height];
+    let mut came_from: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut tracker = SearchTracker::new(observer);
+
+    // straight lines are never longer than the euclidean distance, so it is the only admissible heuristic
+    let mut open_set = BinaryHeap::new();
+    open_set.push(FrontierNode::new(
+        Heuristic::Euclidean.estimate(grid, start, end),
+        *start,
+    ));
+    tracker.pushed(*start, open_set.len());
+    gscore[start.y as usize][start.x as usize] = 0.0;
+
+    while let Some(FrontierNode { point: current, .. }) = open_set.pop() {
+        if closed[current.y as usize][current.x as usize] {
+            continue;
+        }
+        closed[current.y as usize][current.x as usize] = true;
+        tracker.expanded(current);
+
+        if current == *end {
+            break;
+        }
+
+        let parent = came_from[current.y as usize][current.x as usize].unwrap_or(current);
+        for (_, neighbor) in grid.get_surrounding_blocks(&current) {
+            if !grid.is_passable(&neighbor) || closed[neighbor.y as usize][neighbor.x as usize] {
+                continue;
+            }
+
+            // skip the current point when the neighbour can be seen from its parent
+            let via = if line_of_sight(grid, &parent, &neighbor) {
+                parent
+            } else {
+                current
+            };
+            let cost = match segment_cost(grid, &via, &neighbor) {
+                Some(cost) => cost,
+                None => continue,
+            };
+
+            let tentative_gscore = gscore[via.y as usize][via.x as usize] + cost;
+            if tentative_gscore < gscore[neighbor.y as usize][neighbor.x as usize] {
+                came_from[neighbor.y as usize][neighbor.x as usize] = Some(via);
+                tracker.parent_updated(neighbor, via);
+                gscore[neighbor.y as usize][neighbor.x as usize] = tentative_gscore;
+                open_set.push(FrontierNode::new(
+                    tentative_gscore + Heuristic::Euclidean.estimate(grid, &neighbor, end),
+                    neighbor,
+                ));
+                tracker.pushed(neighbor, open_set.len());
+            }
+        }
+    }
+
+    let waypoints = if gscore[end.y as usize][end.x as usize] == f64::INFINITY {
+        None
+    } else {
+        Some(reconstruct_path(&came_from, end))
+    };
+    SearchResult::with_waypoints(grid, waypoints, tracker.stats)
+}
@@ -0,0 +1,585 @@
+use crate::algorithms::comparison::compare;
+use crate::algorithms::heuristic::{Heuristic, WeightedHeuristic};
+use crate::algorithms::jump_point_search;
+use crate::algorithms::{Algorithm, Connectivity, GridMap, GridSize, Pathfinder, Point};
+use crate::display::comparison::print_comparison;
+use crate::display::result::{print_metrics, render_result};
+use crate::display::setup::MapCrowding;
+use crate::generation::cave::CaveSettings;
+use crate::generation::components::{
+    carve_path, regenerate_until_connected, Components, PathRepair, REGENERATE_ATTEMPTS,
+};
+use crate::generation::dungeon::{DungeonSettings, RoomLayout};
+use crate::generation::maze::MazeAlgorithm;
+use crate::generation::terrain::{self, TerrainSettings};
+use crate::generation::{random_seed, Generator};
+use crate::map_file;
+use crate::map_file::moving_ai;
+use std::path::Path;
+
+pub const USAGE: &str = "Usage: path-finder [options]
+
+Runs without prompts when any option is given, with no options the interactive setup starts.
+
+Options:
+  --map <file>            map to search, .map files are read as Moving AI maps
+  --scen <file>           run every Moving AI scenario in the file on --map and check the lengths
+  --size <width>x<height> size of a generated map when no --map is given, up to 100x100
+                          (default 20x10)
+  --generator <name>      how a map is generated: obstacles (default), cave, dungeon, terrain or a
+                          maze, backtracker, prim, kruskal, wilson, eller or division
+  --crowding <level>      obstacle crowding of a generated map: low, medium or high (default medium)
+  --loops <percent>       percentage of maze walls knocked out to add loops (default 0)
+  --fill <percent>        percentage of walls a cave starts with (default 45)
+  --iterations <number>   smoothing passes over a cave (default 4)
+  --birth <number>        wall neighbours that turn an open cave block into a wall (default 5)
+  --survival <number>     wall neighbours a cave wall needs to stay a wall (default 4)
+  --keep-pockets          keep the cave areas cut off from the largest one
+  --layout <name>         how dungeon rooms are laid out: bsp (default) or random, the start and end
+                          are placed in two rooms unless given
+  --room-size <min>-<max> smallest and largest dungeon room side (default 3-8)
+  --scale <number>        size of the terrain's hills and lakes in blocks (default 16)
+  --octaves <number>      layers of terrain noise up to 8, more gives rougher terrain (default 4)
+  --no-rivers             generate terrain without rivers
+  --seed <number>         seed for the generated map, a random one is picked and printed if not given
+  --algorithm <name>      bfs, dfs, dijkstra, astar, greedy, bellman-ford, jps, bi-bfs, bi-astar, theta,
+                          lazy-theta, ida or dstar-lite (default astar)
+  --compare <list>        compare algorithms instead of running one, all or a comma separated list of names
+  --start <x>,<y>         start point, defaults to the map's start or the top left corner
+  --end <x>,<y>           end point, defaults to the map's end or the bottom right corner
+  --connectivity <mode>   4-way, 8-way or 8-way-no-corner-cutting (defaults to the map's)
+  --connect <mode>        when the start and end are not connected: carve through the fewest walls, or
+                          regenerate the map from the following seeds
+  --heuristic <name>      manhattan, euclidean, chebyshev, octile or zero
+  --weight <number>       heuristic weight for A Star, the other informed searches ignore it
+                          (default 1)
+  --no-tui                only print the metrics, without drawing the grid
+  --help                  show this message
+
+Exits with 1 when no path is found (by any compared algorithm) or a scenario is not solved optimally and 2 for invalid options.";
+
+pub struct CliOptions {
+    pub map: Option<String>,
+    pub scenarios: Option<String>,
+    pub size: GridSize,
+    pub generator: String,
+    pub crowding: MapCrowding,
+    pub loops: f64,
+    pub cave: CaveSettings,
+    pub dungeon: DungeonSettings,
+    pub terrain: TerrainSettings,
+    pub seed: Option<u64>,
+    pub algorithm: Algorithm,
+    pub compare: Option<Vec<Algorithm>>,
+    pub start: Option<Point>,
+    pub end: Option<Point>,
+    pub connectivity: Option<Connectivity>,
+    pub repair: Option<PathRepair>,
+    pub heuristic: Option<Heuristic>,
+    pub weight: f64,
+    pub no_tui: bool,
+    pub help: bool,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        CliOptions {
+            map: None,
+            scenarios: None,
+            size: GridSize::new(20, 10),
+            generator: "obstacles".to_string(),
+            crowding: MapCrowding::Medium,
+            loops: 0.0,
+            cave: CaveSettings::new(),
+            dungeon: DungeonSettings::new(),
+            terrain: TerrainSettings::new(),
+            seed: None,
+            algorithm: Algorithm::AStar,
+            compare: None,
+            start: None,
+            end: None,
+            connectivity: None,
+            repair: None,
+            heuristic: None,
+            weight: 1.0,
+            no_tui: false,
+            help: false,
+        }
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<CliOptions, String> {
+    let mut options = CliOptions::default();
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        if flag == "--no-tui" {
+            options.no_tui = true;
+            continue;
+        }
+        if flag == "--keep-pockets" {
+            options.cave = options.cave.set_remove_pockets(false);
+            continue;
+        }
+        if flag == "--no-rivers" {
+            options.terrain = options.terrain.set_rivers(false);
+            continue;
+        }
+        if flag == "--help" || flag == "-h" {
+            options.help = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?
+            .as_str();
+        match flag.as_str() {
+            "--map" => options.map = Some(value.to_string()),
+            "--scen" => options.scenarios = Some(value.to_string()),
+            "--size" => options.size = parse_size(value)?,
+            "--generator" => {
+                if !["obstacles", "cave", "dungeon", "terrain"].contains(&value)
+                    && MazeAlgorithm::from_id(value).is_none()
+                {
+                    return Err(format!("unknown generator `{}`", value));
+                }
+                options.generator = value.to_string();
+            }
+            "--loops" => options.loops = parse_percentage(flag, value)?,
+            "--fill" => options.cave = options.cave.set_fill_ratio(parse_percentage(flag, value)?),
+            "--iterations" => {
+                options.cave = options.cave.set_iterations(parse_number(flag, value)?)
+            }
+            "--birth" => options.cave = options.cave.set_birth_limit(parse_number(flag, value)?),
+            "--survival" => {
+                options.cave = options.cave.set_survival_limit(parse_number(flag, value)?)
+            }
+            "--layout" => {
+                options.dungeon = options.dungeon.set_layout(
+                    RoomLayout::from_id(value)
+                        .ok_or_else(|| format!("unknown layout `{}`", value))?,
+                )
+            }
+            "--room-size" => {
+                let (min, max) = value
+                    .split_once('-')
+                    .ok_or_else(|| format!("room size `{}` must be written as min-max", value))?;
+                let min = parse_number(flag, min)?;
+                let max = parse_number(flag, max)?;
+                if min == 0 || min > max {
+                    return Err(format!("room size `{}` must be written as min-max", value));
+                }
+                options.dungeon = options.dungeon.set_room_size(min, max);
+            }
+            "--scale" => {
+                options.terrain = options.terrain.set_scale(parse_number(flag, value)? as f64)
+            }
+            "--octaves" => {
+                let octaves = parse_number(flag, value)?;
+                if octaves > terrain::MAX_OCTAVES {
+                    return Err(format!(
+                        "{} `{}` must be at most {}",
+                        flag,
+                        value,
+                        terrain::MAX_OCTAVES
+                    ));
+                }
+                options.terrain = options.terrain.set_octaves(octaves)
+            }
+            "--crowding" => {
+                options.crowding = MapCrowding::from_name(value)
+                    .ok_or_else(|| format!("unknown crowding `{}`", value))?
+            }
+            "--seed" => {
+                options.seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("seed `{}` is not a number", value))?,
+                )
+            }
+            "--algorithm" => {
+                options.algorithm = Algorithm::from_id(value)
+                    .ok_or_else(|| format!("unknown algorithm `{}`", value))?
+            }
+            "--compare" => options.compare = Some(parse_algorithms(value)?),
+            "--start" => options.start = Some(parse_point(value)?),
+            "--end" => options.end = Some(parse_point(value)?),
+            "--connectivity" => {
+                options.connectivity = Some(
+                    Connectivity::from_id(value)
+                        .ok_or_else(|| format!("unknown connectivity `{}`", value))?,
+                )
+            }
+            "--connect" => {
+                options.repair = Some(
+                    PathRepair::from_id(value)
+                        .ok_or_else(|| format!("unknown connect mode `{}`", value))?,
+                )
+            }
+            "--heuristic" => {
+                options.heuristic = Some(
+                    Heuristic::from_name(value)
+                        .ok_or_else(|| format!("unknown heuristic `{}`", value))?,
+                )
+            }
+            "--weight" => {
+                options.weight = value
+                    .parse()
+                    .map_err(|_| format!("weight `{}` is not a number", value))?
+            }
+            _ => return Err(format!("unknown option `{}`", flag)),
+        }
+    }
+
+    if options.scenarios.is_some() && options.map.is_none() {
+        return Err("--scen needs the --map the scenarios were written for".to_string());
+    }
+    if options.seed.is_some() && options.map.is_some() {
+        return Err("--seed only applies to generated maps, not to a --map".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_percentage(flag: &str, value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent / 100.0),
+        _ => Err(format!("{} `{}` must be a percentage", flag, value)),
+    }
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<i32, String> {
+    match value.parse::<i32>() {
+        Ok(number) if number >= 0 => Ok(number),
+        _ => Err(format!("{} `{}` must be a positive number", flag, value)),
+    }
+}
+
+fn parse_algorithms(value: &str) -> Result<Vec<Algorithm>, String> {
+    if value == "all" {
+        return Ok(Algorithm::ALL.to_vec());
+    }
+    value
+        .split(',')
+        .map(|id| {
+            Algorithm::from_id(id.trim()).ok_or_else(|| format!("unknown algorithm `{}`", id))
+        })
+        .collect()
+}
+
+fn parse_point(value: &str) -> Result<Point, String> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| format!("point `{}` must be written as x,y", value))?;
+    match (x.trim().parse(), y.trim().parse()) {
+        (Ok(x), Ok(y)) => Ok(Point::new(x, y)),
+        _ => Err(format!("point `{}` must be written as x,y", value)),
+    }
+}
+
+fn parse_size(value: &str) -> Result<GridSize, String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("size `{}` must be written as widthxheight", value))?;
+    let side = 1..=map_file::MAX_DIMENSION;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if side.contains(&width) && side.contains(&height) => {
+            Ok(GridSize::new(width, height))
+        }
+        (Ok(_), Ok(_)) => Err(format!(
+            "size `{}` must be from 1x1 to {}x{}",
+            value,
+            map_file::MAX_DIMENSION,
+            map_file::MAX_DIMENSION
+        )),
+        _ => Err(format!("size `{}` must be written as widthxheight", value)),
+    }
+}
+
+/**
+ * Runs the search described by the arguments and returns the process exit code
+ */
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            return 2;
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let mut grid = match load_grid(&options) {
+        Ok(grid) => grid,
+        Err(error) => {
+            eprintln!("{}", error);
+            return 2;
+        }
+    };
+    if let Some(connectivity) = options.connectivity {
+        grid.set_connectivity(connectivity);
+    }
+
+    if let Some(scenarios) = &options.scenarios {
+        return run_scenarios(&options, grid, scenarios);
+    }
+
+    let start = options.start.unwrap_or(if grid.has_start() {
+        grid.start
+    } else {
+        Point::new(0, 0)
+    });
+    let end = options.end.unwrap_or(if grid.has_end() {
+        grid.end
+    } else {
+        Point::new(grid.size.width - 1, grid.size.height - 1)
+    });
+    if let Err(error) = place_endpoints(&mut grid, &start, &end, options.map.is_none()) {
+        eprintln!("{}", error);
+        return 2;
+    }
+    if let Err(error) = connect_endpoints(&options, &mut grid) {
+        eprintln!("{}", error);
+        return 2;
+    }
+    if let Some(seed) = grid.seed {
+        println!("Seed: {}", seed);
+    }
+    let algorithms = options
+        .compare
+        .clone()
+        .unwrap_or_else(|| vec![options.algorithm]);
+    if algorithms.contains(&Algorithm::JumpPointSearch) && !jump_point_search::supports(&grid) {
+        eprintln!("Warning: {}", jump_point_search::FALLBACK_WARNING);
+    }
+
+    if let Some(algorithms) = &options.compare {
+        if !options.no_tui {
+            grid.render();
+        }
+        let report = compare(&grid, &start, &end, algorithms, &heuristic(&options, &grid));
+        print_comparison(&report);
+        return if report.all_found() { 0 } else { 1 };
+    }
+
+    let pathfinder = create_pathfinder(&options, grid, start, end);
+    let result = pathfinder.run();
+    if options.no_tui {
+        print_metrics(&result);
+    } else {
+        render_result(&pathfinder.grid, &start, &end, &result);
+    }
+
+    if result.found() {
+        0
+    } else {
+        1
+    }
+}
+
+fn load_grid(options: &CliOptions) -> Result<GridMap, String> {
+    if let Some(path) = &options.map {
+        return map_file::load(path).map_err(|error| format!("Failed to load {}: {}", path, error));
+    }
+
+    let mut grid = GridMap::new(options.size);
+    grid.generate(
+        &generator(options),
+        options.seed.unwrap_or_else(random_seed),
+    );
+    Ok(grid)
+}
+
+fn generator(options: &CliOptions) -> Generator {
+    match options.generator.as_str() {
+        "obstacles" => Generator::Obstacles(options.crowding),
+        "cave" => Generator::Cave(options.cave),
+        "dungeon" => Generator::Dungeon(options.dungeon),
+        "terrain" => Generator::Terrain(options.terrain),
+        id => Generator::Maze {
+            algorithm: MazeAlgorithm::from_id(id).expect("generator was checked while parsing"),
+            loop_chance: options.loops,
+        },
+    }
+}
+
+/**
+ * Marks the start and end on the grid, generated maps have any obstacle under them cleared
+ */
+fn place_endpoints(
+    grid: &mut GridMap,
+    start: &Point,
+    end: &Point,
+    clear_obstacles: bool,
+) -> Result<(), String> {
+    for (point, name) in [(start, "start"), (end, "end")] {
+        if !grid.contains(point) {
+            return Err(format!(
+                "the {} {},{} is outside the map",
+                name, point.x, point.y
+            ));
+        }
+        if grid.get_cost(point).is_none() && !clear_obstacles {
+            return Err(format!(
+                "the {} {},{} is an obstacle",
+                name, point.x, point.y
+            ));
+        }
+    }
+
+    grid.place_endpoints(start, end);
+    Ok(())
+}
+
+/**
+ * Repairs a map whose start and end are in different components with the chosen repair, or warns
+ * that no path exists when none was chosen
+ */
+fn connect_endpoints(options: &CliOptions, grid: &mut GridMap) -> Result<(), String> {
+    let (start, end) = (grid.start, grid.end);
+    if Components::new(grid).connected(&start, &end) {
+        return Ok(());
+    }
+
+    match options.repair {
+        Some(PathRepair::Carve) => {
+            let carved = carve_path(grid, &start, &end);
+            println!("Carved {} blocks to connect the start and end", carved);
+        }
+        Some(PathRepair::Regenerate) => {
+            if grid.generator.is_none() {
+                return Err("only generated maps can be regenerated".to_string());
+            }
+            if !regenerate_until_connected(grid) {
+                eprintln!(
+                    "Warning: none of the next {} seeds connect the start and end",
+                    REGENERATE_ATTEMPTS
+                );
+            }
+        }
+        None => eprintln!(
+            "Warning: the start and end are in different components, no path exists between them"
+        ),
+    }
+    Ok(())
+}
+
+fn heuristic(options: &CliOptions, grid: &GridMap) -> WeightedHeuristic {
+    let heuristic = options
+        .heuristic
+        .unwrap_or(Heuristic::for_connectivity(grid.connectivity));
+    WeightedHeuristic::new(heuristic).set_weight(options.weight)
+}
+
+fn create_pathfinder(options: &CliOptions, grid: GridMap, start: Point, end: Point) -> Pathfinder {
+    let heuristic = heuristic(options, &grid);
+    Pathfinder::new(grid, start, end, options.algorithm).set_heuristic(heuristic)
+}
+
+fn run_scenarios(options: &CliOptions, grid: GridMap, path: &str) -> i32 {
+    let map_path = Path::new(options.map.as_deref().unwrap_or_default());
+    let scenarios = match moving_ai::load_scenarios(path).and_then(|scenarios| {
+        for scenario in &scenarios {
+            scenario.check(map_path, &grid)?;
+        }
+        Ok(scenarios)
+    }) {
+        Ok(scenarios) => scenarios,
+        Err(error) => {
+            eprintln!("Failed to load {}: {}", path, error);
+            return 2;
+        }
+    };
+
+    let mut failed = 0;
+    for (i, scenario) in scenarios.iter().enumerate() {
+        let mut scenario_grid = grid.clone();
+        scenario.place_on(&mut scenario_grid);
+        let result = create_pathfinder(options, scenario_grid, scenario.start, scenario.end).run();
+        let optimal = scenario.is_optimal(&result);
+        if !optimal {
+            failed += 1;
+        }
+        println!(
+            "{:>5} {},{} -> {},{} cost {:.8} optimal {:.8} {}",
+            i,
+            scenario.start.x,
+            scenario.start.y,
+            scenario.end.x,
+            scenario.end.y,
+            result.total_cost,
+            scenario.optimal_length,
+            if optimal { "ok" } else { "MISMATCH" }
+        );
+    }
+    println!(
+        "{}/{} scenarios optimal",
+        scenarios.len() - failed,
+        scenarios.len()
+    );
+
+    if failed == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn scenarios_need_a_map() {
+        assert!(parse_args(&args("--scen test.scen")).is_err());
+        assert!(parse_args(&args("--map test.map --scen test.scen")).is_ok());
+        assert_eq!(run(&args("--scen test.scen")), 2);
+    }
+
+    #[test]
+    fn seed_is_refused_with_a_map() {
+        assert!(parse_args(&args("--map test.map --seed 7")).is_err());
+        assert!(parse_args(&args("--seed 7")).is_ok());
+    }
+
+    #[test]
+    fn octaves_past_the_limit_are_refused() {
+        assert!(parse_args(&args("--octaves 8")).is_ok());
+        assert!(parse_args(&args("--octaves 9")).is_err());
+        assert!(parse_args(&args("--octaves 2147483647")).is_err());
+    }
+
+    #[test]
+    fn sizes_past_the_map_file_limit_are_refused() {
+        assert!(parse_args(&args("--size 100x100")).is_ok());
+        assert!(parse_args(&args("--size 101x10")).is_err());
+        assert!(parse_args(&args("--size 10x0")).is_err());
+        assert!(parse_args(&args("--size 100000x100000")).is_err());
+    }
+
+    #[test]
+    fn compare_exits_with_one_when_a_search_finds_no_path() {
+        let dir = std::env::temp_dir();
+        let open = dir.join(format!("path-finder-open-{}.txt", std::process::id()));
+        let walled = dir.join(format!("path-finder-walled-{}.txt", std::process::id()));
+        std::fs::write(&open, "width 3\nheight 1\nmap\nS.E\n").unwrap();
+        std::fs::write(&walled, "width 3\nheight 1\nmap\nS#E\n").unwrap();
+        let compare = |map: &Path| {
+            run(&args(&format!(
+                "--map {} --compare bfs,astar --no-tui",
+                map.display()
+            )))
+        };
+        assert_eq!(compare(&open), 0);
+        assert_eq!(compare(&walled), 1);
+        std::fs::remove_file(open).unwrap();
+        std::fs::remove_file(walled).unwrap();
+    }
+}
@@ -0,0 +1,314 @@
+use crate::algorithms::events::SearchEvent;
+use crate::algorithms::{GridBlock, GridMap, Point};
+use crate::tui::refresh_display;
+use crossterm::{
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind},
+    terminal,
+};
+use std::time::Duration;
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_millis(2000);
+const TRACK_GAP: &str = "    ";
+
+/**
+ * Splits the events into frames, a new frame starts at every expansion so one frame is one step
+ * of the search, and at every new iteration so the cleared grid is shown before it is searched again
+ */
+pub fn split_frames(events: &[SearchEvent]) -> Vec<&[SearchEvent]> {
+    let mut frames = Vec::new();
+    let mut frame_start = 0;
+    for (i, event) in events.iter().enumerate() {
+        if i > frame_start
+            && matches!(
+                event,
+                SearchEvent::Expanded(_)
+                    | SearchEvent::ExpandedFromEnd(_)
+                    | SearchEvent::Iteration { .. }
+            )
+        {
+            frames.push(&events[frame_start..i]);
+            frame_start = i;
+        }
+    }
+    if frame_start < events.len() {
+        frames.push(&events[frame_start..]);
+    }
+    frames
+}
+
+/**
+ * Paints a single event onto the grid, the start and end blocks and the meeting point of a
+ * bidirectional search are never painted over, jump points stay marked until the path is drawn.
+ * A new iteration clears the grid, which the caller does by starting again from the unpainted grid.
+ */
+pub fn apply_event(grid: &mut GridMap, start: &Point, end: &Point, event: &SearchEvent) {
+    if let SearchEvent::Pushed(point) | SearchEvent::Expanded(point) = event {
+        if grid.get_block(point) == &GridBlock::JumpPoint {
+            return;
+        }
+    }
+
+    let mut paint = |point: &Point, block: GridBlock| {
+        if point != start && point != end && grid.get_block(point) != &GridBlock::Meeting {
+            grid.set_block(point, &block);
+        }
+    };
+
+    match event {
+        SearchEvent::Pushed(point) => paint(point, GridBlock::Frontier),
+        SearchEvent::Expanded(point) => paint(point, GridBlock::Visited),
+        SearchEvent::JumpPoint(point) => paint(point, GridBlock::JumpPoint),
+        SearchEvent::PushedFromEnd(point) => paint(point, GridBlock::EndFrontier),
+        SearchEvent::ExpandedFromEnd(point) => paint(point, GridBlock::EndVisited),
+        SearchEvent::Met(point) => paint(point, GridBlock::Meeting),
+        SearchEvent::ParentUpdated { .. } | SearchEvent::Iteration { .. } => {}
+        SearchEvent::PathFound(path) => {
+            for point in path {
+                paint(point, GridBlock::Path);
+            }
+        }
+        SearchEvent::Waypoints(waypoints) => {
+            for point in waypoints {
+                paint(point, GridBlock::Waypoint);
+            }
+        }
+    }
+}
+
+/**
+ * The recorded frames of one search and the copy of the grid they are painted onto
+ *
+ * Iteration: the number and threshold of the iteration being played, for iterative searches
+ */
+struct Track<'a> {
+    name: String,
+    frames: Vec<&'a [SearchEvent]>,
+    canvas: GridMap,
+    expanded: usize,
+    iteration: Option<(usize, f64)>,
+}
+
+/**
+ * Interactive playback of recorded search events, several searches on the same grid are played
+ * in lockstep and drawn next to each other
+ *
+ * Position: the number of frames painted onto each canvas
+ */
+pub struct Playback<'a> {
+    grid: &'a GridMap,
+    start: &'a Point,
+    end: &'a Point,
+    tracks: Vec<Track<'a>>,
+    position: usize,
+    delay: Duration,
+    paused: bool,
+}
+
+impl<'a> Playback<'a> {
+    pub fn new(
+        grid: &'a GridMap,
+        start: &'a Point,
+        end: &'a Point,
+        events: &'a [SearchEvent],
+        delay: Duration,
+    ) -> Self {
+        Playback::side_by_side(grid, start, end, vec![(String::new(), events)], delay)
+    }
+
+    /**
+     * Plays every named search next to each other, all of them searching the same grid
+     */
+    pub fn side_by_side(
+        grid: &'a GridMap,
+        start: &'a Point,
+        end: &'a Point,
+        searches: Vec<(String, &'a [SearchEvent])>,
+        delay: Duration,
+    ) -> Self {
+        let tracks = searches
+            .into_iter()
+            .map(|(name, events)| Track {
+                name,
+                frames: split_frames(events),
+                canvas: grid.clone(),
+                expanded: 0,
+                iteration: None,
+            })
+            .collect();
+
+        Playback {
+            grid,
+            start,
+            end,
+            tracks,
+            position: 0,
+            delay: delay.clamp(MIN_DELAY, MAX_DELAY),
+            paused: false,
+        }
+    }
+
+    fn length(&self) -> usize {
+        self.tracks
+            .iter()
+            .map(|track| track.frames.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.length()
+    }
+
+    /**
+     * Tracks that already finished keep showing their last frame
+     */
+    pub fn step_forward(&mut self) {
+        if self.is_finished() {
+            return;
+        }
+        for track in &mut self.tracks {
+            if let Some(frame) = track.frames.get(self.position) {
+                for event in *frame {
+                    if matches!(
+                        event,
+                        SearchEvent::Expanded(_) | SearchEvent::ExpandedFromEnd(_)
+                    ) {
+                        track.expanded += 1;
+                    }
+                    if let SearchEvent::Iteration { threshold } = event {
+                        let number = track.iteration.map_or(1, |(number, _)| number + 1);
+                        track.iteration = Some((number, *threshold));
+                        track.canvas = self.grid.clone();
+                    }
+                    apply_event(&mut track.canvas, self.start, self.end, event);
+                }
+            }
+        }
+        self.position += 1;
+    }
+
+    /**
+     * Painting can not be undone, so the history up to the previous frame is replayed onto a fresh copy of the grid
+     */
+    pub fn step_back(&mut self) {
+        if self.position == 0 {
+            return;
+        }
+        self.seek(self.position - 1);
+    }
+
+    pub fn seek(&mut self, position: usize) {
+        for track in &mut self.tracks {
+            track.canvas = self.grid.clone();
+            track.expanded = 0;
+            track.iteration = None;
+        }
+        self.position = 0;
+        while self.position < position.min(self.length()) {
+            self.step_forward();
+        }
+    }
+
+    pub fn speed_up(&mut self) {
+        self.delay = (self.delay / 2).max(MIN_DELAY);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.delay = (self.delay * 2).min(MAX_DELAY);
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    fn render(&self) {
+        for y in 0..self.grid.grid.len() {
+            let rows: Vec<String> = self
+                .tracks
+                .iter()
+                .map(|track| track.canvas.render_row(y))
+                .collect();
+            println!("{}", rows.join(TRACK_GAP));
+        }
+
+        let state = if self.is_finished() {
+            "Finished, press Enter to continue"
+        } else if self.paused {
+            "Paused"
+        } else {
+            "Playing"
+        };
+        let mut status = format!("Step {}/{}", self.position, self.length());
+        if self.tracks.len() > 1 {
+            for track in &self.tracks {
+                status.push_str(&format!(" | {}: {} expanded", track.name, track.expanded));
+                if let Some((number, threshold)) = track.iteration {
+                    status.push_str(&format!(", iteration {} ({:.2})", number, threshold));
+                }
+            }
+        } else if let Some((number, threshold)) =
+            self.tracks.first().and_then(|track| track.iteration)
+        {
+            status.push_str(&format!(
+                " | Iteration {}, threshold {:.2}",
+                number, threshold
+            ));
+        }
+        println!(
+            "{} | Delay {}ms | {} | Space: pause, ←/→: step, +/-: speed",
+            status,
+            self.delay.as_millis(),
+            state
+        );
+    }
+
+    /**
+     * Plays the search until the user continues past the final frame
+     */
+    pub fn run(&mut self) {
+        self.render();
+        loop {
+            let waiting = self.paused || self.is_finished();
+            terminal::enable_raw_mode().expect("Failed to enable raw mode");
+            let has_event = waiting || poll(self.delay).expect("Failed to poll for events");
+            let event = if has_event {
+                Some(read().unwrap())
+            } else {
+                None
+            };
+            terminal::disable_raw_mode().expect("Failed to disable raw mode");
+
+            match event {
+                Some(Event::Key(KeyEvent {
+                    code,
+                    kind: KeyEventKind::Press,
+                    ..
+                })) => match code {
+                    KeyCode::Char('q') => {
+                        println!("Quitting...");
+                        std::process::exit(0);
+                    }
+                    KeyCode::Enter if self.is_finished() => return,
+                    KeyCode::Char(' ') => self.toggle_pause(),
+                    KeyCode::Right => {
+                        self.paused = true;
+                        self.step_forward();
+                    }
+                    KeyCode::Left => {
+                        self.paused = true;
+                        self.step_back();
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') => self.speed_up(),
+                    KeyCode::Char('-') => self.slow_down(),
+                    _ => continue,
+                },
+                Some(_) => continue,
+                None => self.step_forward(),
+            }
+
+            refresh_display(self.grid.full_size + 1);
+            self.render();
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::algorithms::comparison::ComparisonReport;
+use crate::algorithms::SearchStatus;
+
+/**
+ * Prints one row per algorithm, paths costing more than the optimal one are flagged
+ */
+pub fn print_comparison(report: &ComparisonReport) {
+    for line in comparison_table(report) {
+        println!("{}", line);
+    }
+}
+
+/**
+ * The lines `print_comparison` prints, a header, a row per algorithm and the optimal cost
+ */
+pub fn comparison_table(report: &ComparisonReport) -> Vec<String> {
+    let mut lines = Vec::new();
+    let name_width = report
+        .comparisons
+        .iter()
+        .map(|comparison| comparison.algorithm.to_name().len())
+        .max()
+        .unwrap_or(0)
+        .max("Algorithm".len());
+    lines.push(format!(
+        "{:<name_width$} {:>10} {:>8} {:>10} {:>15} {:>12}",
+        "Algorithm", "Cost", "Length", "Expanded", "Peak Frontier", "Time"
+    ));
+    for comparison in &report.comparisons {
+        let result = &comparison.result;
+        let (cost, length) = match result.status {
+            SearchStatus::Found => (
+                format!("{:.3}", result.total_cost),
+                result.path_length().to_string(),
+            ),
+            SearchStatus::NotFound => ("no path".to_string(), "-".to_string()),
+        };
+        let note = if report.is_suboptimal(comparison) {
+            "longer than optimal"
+        } else {
+            ""
+        };
+        let row = format!(
+            "{:<name_width$} {:>10} {:>8} {:>10} {:>15} {:>12} {}",
+            comparison.algorithm.to_name(),
+            cost,
+            length,
+            result.nodes_expanded,
+            result.max_frontier_size,
+            format!("{:.3?}", result.runtime),
+            note
+        );
+        lines.push(row.trim_end().to_string());
+    }
+
+    lines.push(match report.optimal_cost {
+        Some(optimal_cost) => format!("Optimal Cost: {:.3}", optimal_cost),
+        None => "No path exists between the start and end".to_string(),
+    });
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::comparison::compare;
+    use crate::algorithms::heuristic::{Heuristic, WeightedHeuristic};
+    use crate::algorithms::Algorithm;
+    use crate::map_file;
+
+    fn table(map: &str, algorithms: &[Algorithm]) -> Vec<String> {
+        let grid = map_file::from_text(map).expect("map is valid");
+        let heuristic = WeightedHeuristic::new(Heuristic::Manhattan);
+        comparison_table(&compare(
+            &grid,
+            &grid.start,
+            &grid.end,
+            algorithms,
+            &heuristic,
+        ))
+    }
+
+    #[test]
+    fn rows_show_the_cost_and_flag_longer_paths() {
+        let lines = table(
+            "width 5\nheight 2\nmap\nS.m.E\n#...#\n",
+            &[Algorithm::BreadthFirstSearch, Algorithm::Dijkstra],
+        );
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Algorithm"));
+        // the cost and length follow the name
+        let columns = |line: &str, algorithm: Algorithm| -> Vec<String> {
+            line.strip_prefix(algorithm.to_name())
+                .expect("rows start with the name")
+                .split_whitespace()
+                .map(str::to_string)
+                .collect()
+        };
+        let bfs = columns(&lines[1], Algorithm::BreadthFirstSearch);
+        assert_eq!(bfs[..2], ["7.000", "4"]);
+        assert!(lines[1].ends_with("longer than optimal"));
+        let dijkstra = columns(&lines[2], Algorithm::Dijkstra);
+        assert_eq!(dijkstra[..2], ["6.000", "6"]);
+        assert!(!lines[2].contains("longer"));
+        assert_eq!(lines[3], "Optimal Cost: 6.000");
+    }
+
+    #[test]
+    fn rows_without_a_path_say_so() {
+        let lines = table("width 3\nheight 1\nmap\nS#E\n", &[Algorithm::AStar]);
+        assert!(lines[1].starts_with("A Star"));
+        assert!(lines[1].contains("no path"));
+        assert_eq!(lines[2], "No path exists between the start and end");
+    }
+}
@@ -1,2 +1,6 @@
+pub mod animation;
+pub mod comparison;
+pub mod replanning;
+pub mod result;
 pub mod setup;
 pub mod welcome;
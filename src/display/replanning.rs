@@ -0,0 +1,220 @@
+use crate::algorithms::d_star_lite::DStarLite;
+use crate::algorithms::events::{EventRecorder, NoopObserver, SearchEvent};
+use crate::algorithms::heuristic::WeightedHeuristic;
+use crate::algorithms::{GridBlock, GridMap, Point};
+use crate::tui::refresh_display;
+use crossterm::{
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind},
+    terminal,
+};
+use std::time::Duration;
+
+const MIN_DELAY: Duration = Duration::from_millis(50);
+const MAX_DELAY: Duration = Duration::from_millis(2000);
+const DEFAULT_DELAY: Duration = Duration::from_millis(300);
+
+/**
+ * A robot walking the path from the start to the end while the user adds and removes obstacles,
+ * the path is repaired after every change instead of searched again
+ *
+ * Repaired: the points expanded by the last repair
+ * From scratch: the points a new search from the robot would have expanded for the same path
+ */
+pub struct ReplanningDemo {
+    grid: GridMap,
+    heuristic: WeightedHeuristic,
+    planner: DStarLite,
+    robot: Point,
+    cursor: Point,
+    path: Vec<Point>,
+    cost: f64,
+    repaired: Vec<Point>,
+    from_scratch: i32,
+    delay: Duration,
+    paused: bool,
+}
+
+impl ReplanningDemo {
+    /**
+     * A delay of zero walks at the default pace, the demo can not be watched without one
+     */
+    pub fn new(grid: &GridMap, heuristic: &WeightedHeuristic, delay: Duration) -> ReplanningDemo {
+        let mut grid = grid.clone();
+        let robot = grid.start;
+        grid.set_block(&robot, &GridBlock::Empty);
+
+        let delay = if delay.is_zero() {
+            DEFAULT_DELAY
+        } else {
+            delay.clamp(MIN_DELAY, MAX_DELAY)
+        };
+        let mut demo = ReplanningDemo {
+            planner: DStarLite::new(&grid, &robot, &grid.end, heuristic),
+            heuristic: *heuristic,
+            robot,
+            cursor: Point::new(grid.size.width / 2, grid.size.height / 2),
+            path: Vec::new(),
+            cost: f64::INFINITY,
+            repaired: Vec::new(),
+            from_scratch: 0,
+            delay,
+            paused: false,
+            grid,
+        };
+        demo.repair();
+        demo
+    }
+
+    fn repair(&mut self) {
+        let mut recorder = EventRecorder::new();
+        let result = self.planner.compute_path(&self.grid, &mut recorder);
+        self.repaired = recorder
+            .events
+            .into_iter()
+            .filter_map(|event| match event {
+                SearchEvent::Expanded(point) => Some(point),
+                _ => None,
+            })
+            .collect();
+        self.cost = result.total_cost;
+        self.path = result.path;
+
+        self.from_scratch =
+            DStarLite::new(&self.grid, &self.robot, &self.grid.end, &self.heuristic)
+                .compute_path(&self.grid, &mut NoopObserver)
+                .nodes_expanded;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.robot == self.grid.end
+    }
+
+    /**
+     * Moves the robot one block along the path, it waits where it is while there is no path
+     */
+    pub fn step_forward(&mut self) {
+        if let Some(next) = self.path.get(1) {
+            self.robot = *next;
+            self.planner.move_start(&self.grid, &self.robot);
+            self.repair();
+        }
+    }
+
+    /**
+     * Adds or removes an obstacle under the cursor, the robot and the end can not be covered
+     */
+    pub fn toggle_obstacle(&mut self) {
+        if self.cursor == self.robot || self.cursor == self.grid.end {
+            return;
+        }
+        if self.grid.get_block(&self.cursor) == &GridBlock::Obstacle {
+            self.grid.set_block(&self.cursor, &GridBlock::Empty);
+        } else {
+            self.grid.set_block(&self.cursor, &GridBlock::Obstacle);
+        }
+        self.planner.update_cells(&self.grid, &[self.cursor]);
+        self.repair();
+    }
+
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        self.cursor.x = (self.cursor.x + dx).rem_euclid(self.grid.size.width);
+        self.cursor.y = (self.cursor.y + dy).rem_euclid(self.grid.size.height);
+    }
+
+    fn render(&self) {
+        let mut canvas = self.grid.clone();
+        for point in &self.repaired {
+            canvas.set_block(point, &GridBlock::Visited);
+        }
+        for point in &self.path {
+            canvas.set_block(point, &GridBlock::Path);
+        }
+        canvas.set_block(&self.robot, &GridBlock::Start);
+        canvas.set_block(&self.grid.end, &GridBlock::End);
+
+        // the cursor shows the block it would leave behind
+        let selector = if self.grid.get_block(&self.cursor) == &GridBlock::Obstacle {
+            GridBlock::Empty
+        } else {
+            GridBlock::Obstacle
+        };
+        canvas.render_with_selector(&self.cursor, &selector);
+
+        if self.path.is_empty() {
+            println!(
+                "Robot at {},{} | No path, remove obstacles to open one",
+                self.robot.x, self.robot.y
+            );
+        } else {
+            println!(
+                "Robot at {},{} | Path cost {:.2} | Last repair expanded {} ({} from scratch)",
+                self.robot.x,
+                self.robot.y,
+                self.cost,
+                self.repaired.len(),
+                self.from_scratch
+            );
+        }
+
+        let state = if self.is_finished() {
+            "Arrived, press Enter to continue"
+        } else if self.paused {
+            "Paused"
+        } else {
+            "Walking"
+        };
+        println!(
+            "{} | Delay {}ms | Arrows: cursor, Enter: toggle obstacle, Space: pause, +/-: speed",
+            state,
+            self.delay.as_millis()
+        );
+    }
+
+    /**
+     * Walks the robot until it arrives and the user continues
+     */
+    pub fn run(&mut self) {
+        self.render();
+        loop {
+            let waiting = self.paused || self.is_finished();
+            terminal::enable_raw_mode().expect("Failed to enable raw mode");
+            let has_event = waiting || poll(self.delay).expect("Failed to poll for events");
+            let event = if has_event {
+                Some(read().unwrap())
+            } else {
+                None
+            };
+            terminal::disable_raw_mode().expect("Failed to disable raw mode");
+
+            match event {
+                Some(Event::Key(KeyEvent {
+                    code,
+                    kind: KeyEventKind::Press,
+                    ..
+                })) => match code {
+                    KeyCode::Char('q') => {
+                        println!("Quitting...");
+                        std::process::exit(0);
+                    }
+                    KeyCode::Enter if self.is_finished() => return,
+                    KeyCode::Enter => self.toggle_obstacle(),
+                    KeyCode::Char(' ') => self.paused = !self.paused,
+                    KeyCode::Up => self.move_cursor(0, -1),
+                    KeyCode::Down => self.move_cursor(0, 1),
+                    KeyCode::Left => self.move_cursor(-1, 0),
+                    KeyCode::Right => self.move_cursor(1, 0),
+                    KeyCode::Char('+') | KeyCode::Char('=') => {
+                        self.delay = (self.delay / 2).max(MIN_DELAY)
+                    }
+                    KeyCode::Char('-') => self.delay = (self.delay * 2).min(MAX_DELAY),
+                    _ => continue,
+                },
+                Some(_) => continue,
+                None => self.step_forward(),
+            }
+
+            refresh_display(self.grid.full_size + 2);
+            self.render();
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::algorithms::{GridBlock, GridMap, Point, SearchResult, SearchStatus};
+
+/**
+ * Paints the path of the result onto a copy of the grid and renders it with the search metrics
+ */
+pub fn render_result(grid: &GridMap, start: &Point, end: &Point, result: &SearchResult) {
+    let mut grid = grid.clone();
+    for point in &result.path {
+        grid.set_block(point, &GridBlock::Path);
+    }
+    for point in &result.waypoints {
+        grid.set_block(point, &GridBlock::Waypoint);
+    }
+    grid.set_block(start, &GridBlock::Start);
+    grid.set_block(end, &GridBlock::End);
+    grid.render();
+    print_metrics(result);
+}
+
+pub fn print_metrics(result: &SearchResult) {
+    match result.status {
+        SearchStatus::Found => {
+            println!("Path Length: {}", result.path_length());
+            println!("Path Cost: {}", result.total_cost);
+            if !result.waypoints.is_empty() {
+                println!("Waypoints: {}", result.waypoints.len());
+            }
+        }
+        SearchStatus::NotFound => println!("No path found"),
+    }
+    println!("Nodes Expanded: {}", result.nodes_expanded);
+    println!("Nodes Generated: {}", result.nodes_generated);
+    println!("Max Frontier Size: {}", result.max_frontier_size);
+    if result.iterations > 0 {
+        println!("Iterations: {}", result.iterations);
+        println!("Re-expansions: {}", result.re_expansions);
+    }
+    println!("Time: {:.3?}", result.runtime);
+}
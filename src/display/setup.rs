@@ -1,18 +1,36 @@
+use crate::algorithms::heuristic::{Heuristic, WeightedHeuristic};
+use crate::algorithms::{Algorithm, Connectivity, GridBlock, GridMap, GridSize, Point};
+use crate::generation::cave::CaveSettings;
+use crate::generation::components::{
+    carve_path, regenerate_until_connected, Components, PathRepair, REGENERATE_ATTEMPTS,
+};
+use crate::generation::dungeon::{DungeonSettings, RoomLayout};
+use crate::generation::maze::MazeAlgorithm;
+use crate::generation::terrain::TerrainSettings;
+use crate::generation::{random_seed, Generator};
+use crate::map_file;
+use crate::tui::confirm::Confirm;
 use crate::tui::number_input::NumberInput;
 use crate::tui::option_select::OptionSelect;
 use crate::tui::refresh_display;
-use crate::{Algorithm, GridBlock, GridMap, GridSize, Point, SetupConfig};
+use crate::tui::text_input::TextInput;
+use crate::SetupConfig;
 use crossterm::{
     event::{read, Event, KeyCode, KeyEvent, KeyEventKind},
     terminal,
 };
+use std::time::Duration;
+
+const COMPARE_OPTION: &str = "Compare algorithms";
 
 pub enum MapBuilderMode {
     Obstacle,
+    Terrain,
     Start,
     End,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MapCrowding {
     Low,
     Medium,
@@ -20,6 +38,22 @@ pub enum MapCrowding {
 }
 
 impl MapCrowding {
+    pub const ALL: [MapCrowding; 3] = [MapCrowding::Low, MapCrowding::Medium, MapCrowding::High];
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            MapCrowding::Low => "Low",
+            MapCrowding::Medium => "Medium",
+            MapCrowding::High => "High",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<MapCrowding> {
+        MapCrowding::ALL
+            .into_iter()
+            .find(|crowding| crowding.to_name().eq_ignore_ascii_case(name))
+    }
+
     pub fn min_obstacle_size(&self) -> i32 {
         match self {
             MapCrowding::Low => 1,
@@ -46,37 +80,248 @@ impl MapCrowding {
 }
 
 pub fn config_setup() -> SetupConfig {
+    let mut algorithm_selection = OptionSelect::new().set_title("Select an algorithm:");
+    for option in Algorithm::ALL {
+        algorithm_selection = algorithm_selection.add_option(option.to_name());
+    }
+    let algorithm_selection = algorithm_selection.add_option(COMPARE_OPTION).ask();
+    let algorithms = if algorithm_selection == COMPARE_OPTION {
+        ask_for_compared_algorithms()
+    } else {
+        vec![*Algorithm::ALL
+            .iter()
+            .find(|option| option.to_name() == algorithm_selection)
+            .expect("algorithm selection has no matching algorithm")]
+    };
+
+    let mut connectivity_selection = OptionSelect::new().set_title("Select movement:");
+    for option in Connectivity::ALL {
+        connectivity_selection = connectivity_selection.add_option(option.to_name());
+    }
+    let connectivity = Connectivity::from_name(&connectivity_selection.ask())
+        .expect("movement selection has no matching connectivity");
+
+    let mut heuristic = WeightedHeuristic::new(Heuristic::for_connectivity(connectivity));
+    if algorithms.iter().any(|algorithm| algorithm.is_informed()) {
+        let mut heuristic_selection = OptionSelect::new().set_title("Select a heuristic:");
+        for option in Heuristic::ALL {
+            heuristic_selection = heuristic_selection.add_option(option.to_name());
+        }
+        heuristic = WeightedHeuristic::new(
+            Heuristic::from_name(&heuristic_selection.ask())
+                .expect("heuristic selection has no matching heuristic"),
+        );
+    }
+    if algorithms.contains(&Algorithm::AStar) {
+        let weight = NumberInput::new()
+            .set_message("Enter the heuristic weight as a percentage (100 for regular A Star):")
+            .set_min(0)
+            .set_max(1000)
+            .ask();
+        heuristic = heuristic.set_weight(weight as f64 / 100.0);
+    }
+
+    let mut animation_delay = 0;
+    if algorithms.len() <= 2 {
+        animation_delay = NumberInput::new()
+            .set_message(if algorithms.len() == 2 {
+                "Enter the animation delay in milliseconds (0 to skip the side by side animation):"
+            } else {
+                "Enter the animation delay in milliseconds (0 to skip the animation):"
+            })
+            .set_min(0)
+            .set_max(1000)
+            .ask();
+    }
+
+    SetupConfig::new(
+        algorithms,
+        connectivity,
+        heuristic,
+        Duration::from_millis(animation_delay as u64),
+    )
+}
+
+/**
+ * Asks which algorithms to compare, choosing them one by one until at least one is picked
+ */
+fn ask_for_compared_algorithms() -> Vec<Algorithm> {
+    let selection = OptionSelect::new()
+        .set_title("Select the algorithms to compare:")
+        .add_option("All algorithms")
+        .add_option("Choose algorithms")
+        .ask();
+    if selection == "All algorithms" {
+        return Algorithm::ALL.to_vec();
+    }
+
+    loop {
+        let mut algorithms = Vec::new();
+        for algorithm in Algorithm::ALL {
+            let include = Confirm::new()
+                .set_message(&format!("Compare {}?", algorithm.to_name()))
+                .ask();
+            if include {
+                algorithms.push(algorithm);
+            } else {
+                refresh_display(2);
+            }
+        }
+        if !algorithms.is_empty() {
+            return algorithms;
+        }
+    }
+}
+
+/**
+ * Builds a new map or loads one from a file, asking for the start and end if the file has none.
+ * A new map moves with the selected connectivity, a loaded map keeps the one it was saved with.
+ */
+pub fn map_setup(connectivity: Connectivity) -> GridMap {
+    let source = OptionSelect::new()
+        .set_title("Select map source:")
+        .add_option("Build a new map")
+        .add_option("Load map from file")
+        .ask();
+
+    let mut grid = match source.as_str() {
+        "Build a new map" => {
+            let mut grid = GridMap::new(ask_for_grid_size());
+            grid.set_connectivity(connectivity);
+            grid = map_builder(MapBuilderMode::Obstacle, grid);
+            map_builder(MapBuilderMode::Terrain, grid)
+        }
+        "Load map from file" => {
+            let grid = load_map_prompt();
+            if grid.connectivity != connectivity {
+                println!(
+                    "The map uses {} movement, which is kept over the selected {}",
+                    grid.connectivity.to_name(),
+                    connectivity.to_name()
+                );
+            }
+            grid
+        }
+        _ => panic!("map source has no matching source option"),
+    };
+
+    if !grid.has_start() {
+        grid = map_builder(MapBuilderMode::Start, grid);
+    }
+    if !grid.has_end() {
+        grid = map_builder(MapBuilderMode::End, grid);
+    }
+    grid
+}
+
+fn ask_for_grid_size() -> GridSize {
     let width = NumberInput::new()
         .set_message("Enter the gird width:")
         .set_min(5)
-        .set_max(100)
-        .ask() as i32;
+        .set_max(map_file::MAX_DIMENSION)
+        .ask();
     let height = NumberInput::new()
         .set_message("Enter the grid height:")
         .set_min(5)
-        .set_max(100)
-        .ask() as i32;
-
-    let algorithm_selection = OptionSelect::new()
-        .set_title("Select an algorithm:")
-        .add_option("Breadth First Search")
-        .add_option("Depth First Search")
-        .add_option("Dijkstra")
-        .add_option("A Star")
-        .add_option("Greedy Best First Search")
-        .add_option("Bellman Ford")
+        .set_max(map_file::MAX_DIMENSION)
         .ask();
-    let algorithm = match algorithm_selection.as_str() {
-        "Breadth First Search" => Algorithm::BreadthFirstSearch,
-        "Depth First Search" => Algorithm::DepthFirstSearch,
-        "Dijkstra" => Algorithm::Dijkstra,
-        "A Star" => Algorithm::AStar,
-        "Greedy Best First Search" => Algorithm::GreedyBestFirstSearch,
-        "Bellman Ford" => Algorithm::BellmanFord,
-        _ => panic!("algorithm selection has no matching algorithm"),
-    };
+    GridSize::new(width, height)
+}
 
-    SetupConfig::new(GridSize::new(width, height), algorithm)
+fn load_map_prompt() -> GridMap {
+    loop {
+        let path = TextInput::new()
+            .set_message("Enter the path of the map file (Esc to quit):")
+            .ask();
+        match map_file::load(&path) {
+            Ok(grid) => return grid,
+            Err(error) => {
+                let retry = Confirm::new()
+                    .set_message(&format!("Failed to load {}: {}, try again?", path, error))
+                    .ask();
+                if !retry {
+                    std::process::exit(0);
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Warns when the start and end are in different components and offers to connect them
+ */
+pub fn connect_endpoints_prompt(grid: &mut GridMap) {
+    let (start, end) = (grid.start, grid.end);
+    let components = Components::new(grid);
+    if components.connected(&start, &end) {
+        return;
+    }
+
+    println!(
+        "Warning: the start and end are in different components ({} in total), no path exists between them",
+        components.count()
+    );
+    let mut repair_selection = OptionSelect::new()
+        .set_title("Select how to connect them:")
+        .add_option("Keep the map");
+    for repair in PathRepair::ALL {
+        if repair != PathRepair::Regenerate || grid.generator.is_some() {
+            repair_selection = repair_selection.add_option(repair.to_name());
+        }
+    }
+
+    let selection = repair_selection.ask();
+    match PathRepair::ALL
+        .into_iter()
+        .find(|repair| repair.to_name() == selection)
+    {
+        Some(PathRepair::Carve) => {
+            let carved = carve_path(grid, &start, &end);
+            println!("Carved {} blocks to connect the start and end", carved);
+        }
+        Some(PathRepair::Regenerate) => {
+            if regenerate_until_connected(grid) {
+                println!("Seed: {}", grid.seed.expect("regenerated maps have a seed"));
+            } else {
+                println!(
+                    "Warning: none of the next {} seeds connect the start and end",
+                    REGENERATE_ATTEMPTS
+                );
+            }
+        }
+        None => {}
+    }
+}
+
+pub fn save_map_prompt(grid: &GridMap) {
+    let confirm_save = Confirm::new()
+        .set_message("Would you like to save the map?")
+        .ask();
+    if !confirm_save {
+        refresh_display(2);
+        return;
+    }
+
+    loop {
+        let path = TextInput::new()
+            .set_message("Enter the path to save the map to (Esc to quit):")
+            .ask();
+        match map_file::save(grid, &path) {
+            Ok(()) => {
+                println!("Saved the map to {}", path);
+                return;
+            }
+            Err(error) => {
+                let retry = Confirm::new()
+                    .set_message(&format!("Failed to save {}: {}, try again?", path, error))
+                    .ask();
+                if !retry {
+                    refresh_display(2);
+                    return;
+                }
+            }
+        }
+    }
 }
 
 pub fn map_builder(mode: MapBuilderMode, mut grid: GridMap) -> GridMap {
@@ -87,40 +332,55 @@ pub fn map_builder(mode: MapBuilderMode, mut grid: GridMap) -> GridMap {
                 .add_option("Manual")
                 .add_option("Auto")
                 .add_option("Edit Auto")
+                .add_option("Maze")
+                .add_option("Cave")
+                .add_option("Dungeon")
+                .add_option("Noise Terrain")
                 .ask();
 
-            match obstacle_creation.as_str() {
-                "Auto" => {
-                    let crowding = ask_for_crowding();
-                    grid.generate_obstacles(crowding);
-                }
-                "Edit Auto" | "Manual" => {
-                    if "Edit Auto" == obstacle_creation.as_str() {
-                        let crowding = ask_for_crowding();
-                        grid.generate_obstacles(crowding);
-                    }
-
-                    let mut block_position = Point::new(grid.size.width / 2, grid.size.height / 2);
-                    println!("Press 'S' to save and continue or 'Q' to quit");
-                    loop {
-                        let finished =
-                            placement_loop(&mut grid, &GridBlock::Obstacle, &mut block_position);
-                        if finished {
-                            refresh_display(grid.full_size);
-                            break;
-                        }
-                        if grid.get_block(&block_position) == &GridBlock::Obstacle {
-                            grid.set_block(&block_position, &GridBlock::Empty);
-                        } else {
-                            grid.set_block(&block_position, &GridBlock::Obstacle);
-                        }
-                        refresh_display(grid.full_size);
-                    }
-                    refresh_display(1);
+            let generator = match obstacle_creation.as_str() {
+                "Auto" | "Edit Auto" => Some(Generator::Obstacles(ask_for_crowding())),
+                "Maze" => {
+                    let (algorithm, loop_chance) = ask_for_maze();
+                    Some(Generator::Maze {
+                        algorithm,
+                        loop_chance,
+                    })
                 }
+                "Cave" => Some(Generator::Cave(ask_for_cave())),
+                "Dungeon" => Some(Generator::Dungeon(ask_for_dungeon())),
+                "Noise Terrain" => Some(Generator::Terrain(ask_for_terrain())),
+                "Manual" => None,
                 _ => panic!("obstacle generation has no matching generation option"),
             };
+
+            if let Some(generator) = generator {
+                let seed = ask_for_seed();
+                grid.generate(&generator, seed);
+                println!("Seed: {}", seed);
+            }
+            if obstacle_creation == "Manual" || obstacle_creation == "Edit Auto" {
+                paint_loop(&mut grid, &GridBlock::Obstacle);
+            }
         }
+        MapBuilderMode::Terrain => loop {
+            let mut terrain_selection = OptionSelect::new()
+                .set_title("Select terrain to paint:")
+                .add_option("Done");
+            for terrain in GridBlock::TERRAIN {
+                terrain_selection = terrain_selection.add_option(&terrain_option(&terrain));
+            }
+
+            let selection = terrain_selection.ask();
+            let terrain = match GridBlock::TERRAIN
+                .iter()
+                .find(|terrain| terrain_option(terrain) == selection)
+            {
+                Some(terrain) => *terrain,
+                None => break,
+            };
+            paint_loop(&mut grid, &terrain);
+        },
         MapBuilderMode::Start | MapBuilderMode::End => {
             let block = match mode {
                 MapBuilderMode::Start => GridBlock::Start,
@@ -146,33 +406,162 @@ pub fn map_builder(mode: MapBuilderMode, mut grid: GridMap) -> GridMap {
     grid
 }
 
+fn terrain_option(terrain: &GridBlock) -> String {
+    format!(
+        "{} {} (cost {})",
+        terrain.to_styled_block(),
+        terrain.to_name(),
+        terrain.movement_cost().unwrap_or(f64::INFINITY)
+    )
+}
+
+/**
+ * Toggles the block on and off at the selected position until the user saves, a painted map can
+ * no longer be generated again from its seed
+ */
+fn paint_loop(grid: &mut GridMap, block: &GridBlock) {
+    let mut block_position = Point::new(grid.size.width / 2, grid.size.height / 2);
+    println!("Press 'S' to save and continue or 'Q' to quit");
+    loop {
+        let finished = placement_loop(grid, block, &mut block_position);
+        if finished {
+            refresh_display(grid.full_size);
+            break;
+        }
+        if grid.get_block(&block_position) == block {
+            grid.set_block(&block_position, &GridBlock::Empty);
+        } else {
+            grid.set_block(&block_position, block);
+        }
+        grid.clear_generation();
+        refresh_display(grid.full_size);
+    }
+    refresh_display(1);
+}
+
 fn ask_for_crowding() -> MapCrowding {
-    let crowding_selection = OptionSelect::new()
-        .set_title("Select obstacle crowding:")
-        .add_option("Low")
-        .add_option("Medium")
-        .add_option("High")
+    let mut crowding_selection = OptionSelect::new().set_title("Select obstacle crowding:");
+    for option in MapCrowding::ALL {
+        crowding_selection = crowding_selection.add_option(option.to_name());
+    }
+    MapCrowding::from_name(&crowding_selection.ask())
+        .expect("crowding selection has no matching crowding option")
+}
+
+/**
+ * Asks for the seed to generate the map from, a random seed is picked when the input is left empty
+ */
+fn ask_for_seed() -> u64 {
+    loop {
+        let input = TextInput::new()
+            .set_message("Enter a seed (leave empty for a random one):")
+            .allow_empty()
+            .ask();
+        if input.is_empty() {
+            return random_seed();
+        }
+        if let Ok(seed) = input.parse() {
+            return seed;
+        }
+    }
+}
+
+fn ask_for_maze() -> (MazeAlgorithm, f64) {
+    let mut maze_selection = OptionSelect::new().set_title("Select maze generator:");
+    for option in MazeAlgorithm::ALL {
+        maze_selection = maze_selection.add_option(option.to_name());
+    }
+    let maze_selection = maze_selection.ask();
+    let algorithm = *MazeAlgorithm::ALL
+        .iter()
+        .find(|option| option.to_name() == maze_selection)
+        .expect("maze selection has no matching maze generator");
+
+    let loops = NumberInput::new()
+        .set_message("Enter the percentage of walls to knock out for loops (0 for a perfect maze):")
+        .set_min(0)
+        .set_max(100)
         .ask();
+    (algorithm, loops as f64 / 100.0)
+}
 
-    match crowding_selection.as_str() {
-        "Low" => MapCrowding::Low,
-        "Medium" => MapCrowding::Medium,
-        "High" => MapCrowding::High,
-        _ => panic!("crowding selection has no matching crowding option"),
+fn ask_for_cave() -> CaveSettings {
+    let fill_ratio = NumberInput::new()
+        .set_message("Enter the percentage of walls to start the cave with (45 is typical):")
+        .set_min(0)
+        .set_max(100)
+        .ask();
+    let iterations = NumberInput::new()
+        .set_message("Enter the number of smoothing passes (4 is typical):")
+        .set_min(0)
+        .set_max(20)
+        .ask();
+    let remove_pockets = Confirm::new()
+        .set_message("Would you like to fill the pockets cut off from the largest cave?")
+        .ask();
+    if !remove_pockets {
+        refresh_display(2);
     }
+
+    CaveSettings::new()
+        .set_fill_ratio(fill_ratio as f64 / 100.0)
+        .set_iterations(iterations)
+        .set_remove_pockets(remove_pockets)
+}
+
+fn ask_for_dungeon() -> DungeonSettings {
+    let mut layout_selection = OptionSelect::new().set_title("Select room layout:");
+    for option in RoomLayout::ALL {
+        layout_selection = layout_selection.add_option(option.to_name());
+    }
+    let layout_selection = layout_selection.ask();
+    let layout = *RoomLayout::ALL
+        .iter()
+        .find(|option| option.to_name() == layout_selection)
+        .expect("layout selection has no matching room layout");
+
+    let place_endpoints = Confirm::new()
+        .set_message("Would you like the start and end placed in different rooms?")
+        .ask();
+    if !place_endpoints {
+        refresh_display(2);
+    }
+
+    DungeonSettings::new()
+        .set_layout(layout)
+        .set_place_endpoints(place_endpoints)
+}
+
+fn ask_for_terrain() -> TerrainSettings {
+    let scale = NumberInput::new()
+        .set_message("Enter the size of the hills and lakes in blocks (16 is typical):")
+        .set_min(2)
+        .set_max(100)
+        .ask();
+    let rivers = Confirm::new()
+        .set_message("Would you like rivers running through the terrain?")
+        .ask();
+    if !rivers {
+        refresh_display(2);
+    }
+
+    TerrainSettings::new()
+        .set_scale(scale as f64)
+        .set_rivers(rivers)
 }
 
 fn placement_loop(grid: &mut GridMap, block: &GridBlock, block_position: &mut Point) -> bool {
-    grid.render_with_selector(&block_position, &block);
+    grid.render_with_selector(block_position, block);
     loop {
         terminal::enable_raw_mode().expect("Failed to enable raw mode");
         let event = read().unwrap();
-        match event {
-            Event::Key(KeyEvent {
-                code,
-                kind: KeyEventKind::Press,
-                ..
-            }) => match code {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        {
+            match code {
                 KeyCode::Char('q') => {
                     terminal::disable_raw_mode().expect("Failed to disable raw mode");
                     println!("Quitting...");
@@ -186,7 +575,6 @@ fn placement_loop(grid: &mut GridMap, block: &GridBlock, block_position: &mut Po
                     if block_position.y > 0 {
                         block_position.y -= 1;
                     } else {
-                        println!("{} {}", block_position.y, grid.size.height - 1);
                         block_position.y = grid.size.height - 1;
                     }
                 }
@@ -216,11 +604,10 @@ fn placement_loop(grid: &mut GridMap, block: &GridBlock, block_position: &mut Po
                     return false;
                 }
                 _ => {}
-            },
-            _ => {}
+            }
         }
         terminal::disable_raw_mode().expect("Failed to disable raw mode");
         refresh_display(grid.full_size);
-        grid.render_with_selector(&block_position, &block);
+        grid.render_with_selector(block_position, block);
     }
 }
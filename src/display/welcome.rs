@@ -4,22 +4,24 @@ pub fn welcome() {
     println!("      Created by cqb13        ");
     println!("      GitHub: github.com/cqb13");
     println!("==============================");
-    print!("\n");
+    println!();
 
     println!("Controls:");
     println!("  Move with arrows (←↑↓→)");
     println!("  Enter to select");
     println!("  'q' to quit");
-    print!("\n");
+    println!("  Space to pause the animation, ←→ to step, +/- to change speed");
+    println!();
 
     println!("Instructions:");
-    println!("  Create a grid with obstacles.");
+    println!("  Create a grid with obstacles, or load a saved map.");
+    println!("  Paint terrain that costs more to cross.");
     println!("  Select a start and end point.");
     println!("  Select an algorithm to find the best path.");
     println!("  Watch the algorithm find the best path.");
-    print!("\n");
+    println!();
 
     println!("Enjoy the program!");
     println!("==============================");
-    print!("\n");
+    println!();
 }
@@ -0,0 +1,205 @@
+/*!
+ * Cellular automaton caves
+ * https://en.wikipedia.org/wiki/Cellular_automaton
+ *
+ * The grid is filled with random walls and then smoothed, a wall survives a pass when enough
+ * of its eight neighbours are walls and an open block turns into a wall when even more are.
+ * Blocks outside the grid count as walls so the caves close off at the edges.
+ */
+use super::components::Components;
+use super::fill;
+use crate::algorithms::{Connectivity, GridBlock, GridMap, Point};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaveSettings {
+    pub fill_ratio: f64,
+    pub iterations: i32,
+    pub birth_limit: i32,
+    pub survival_limit: i32,
+    pub remove_pockets: bool,
+}
+
+impl Default for CaveSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaveSettings {
+    pub fn new() -> CaveSettings {
+        CaveSettings {
+            fill_ratio: 0.45,
+            iterations: 4,
+            birth_limit: 5,
+            survival_limit: 4,
+            remove_pockets: true,
+        }
+    }
+
+    pub fn set_fill_ratio(mut self, fill_ratio: f64) -> Self {
+        self.fill_ratio = fill_ratio;
+        self
+    }
+
+    pub fn set_iterations(mut self, iterations: i32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /**
+     * The number of wall neighbours that turns an open block into a wall
+     */
+    pub fn set_birth_limit(mut self, birth_limit: i32) -> Self {
+        self.birth_limit = birth_limit;
+        self
+    }
+
+    /**
+     * The number of wall neighbours a wall needs to stay a wall
+     */
+    pub fn set_survival_limit(mut self, survival_limit: i32) -> Self {
+        self.survival_limit = survival_limit;
+        self
+    }
+
+    /**
+     * Fills every open area except the largest one
+     */
+    pub fn set_remove_pockets(mut self, remove_pockets: bool) -> Self {
+        self.remove_pockets = remove_pockets;
+        self
+    }
+}
+
+pub fn generate(grid: &mut GridMap, settings: &CaveSettings, rng: &mut impl Rng) {
+    fill(grid, GridBlock::Empty);
+    let mut walls: Vec<Vec<bool>> = (0..grid.size.height)
+        .map(|_| {
+            (0..grid.size.width)
+                .map(|_| rng.gen_bool(settings.fill_ratio))
+                .collect()
+        })
+        .collect();
+
+    for _ in 0..settings.iterations {
+        walls = smooth(&walls, settings);
+    }
+
+    for (y, row) in walls.iter().enumerate() {
+        for (x, wall) in row.iter().enumerate() {
+            if *wall {
+                grid.set_block(&Point::new(x as i32, y as i32), &GridBlock::Obstacle);
+            }
+        }
+    }
+
+    if settings.remove_pockets {
+        remove_pockets(grid);
+    }
+}
+
+fn smooth(walls: &[Vec<bool>], settings: &CaveSettings) -> Vec<Vec<bool>> {
+    let height = walls.len() as i32;
+    let width = walls.first().map_or(0, |row| row.len()) as i32;
+    let is_wall = |x: i32, y: i32| {
+        x < 0 || y < 0 || x >= width || y >= height || walls[y as usize][x as usize]
+    };
+
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let mut neighbours = 0;
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            if (dx, dy) != (0, 0) && is_wall(x + dx, y + dy) {
+                                neighbours += 1;
+                            }
+                        }
+                    }
+                    if is_wall(x, y) {
+                        neighbours >= settings.survival_limit
+                    } else {
+                        neighbours >= settings.birth_limit
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * Fills every open area that is not connected to the largest one, areas are connected orthogonally
+ * so the cave stays in one piece whatever the movement rules are
+ */
+fn remove_pockets(grid: &mut GridMap) {
+    let mut four_way = grid.clone();
+    four_way.set_connectivity(Connectivity::FourWay);
+    let components = Components::new(&four_way);
+    let largest = components.largest();
+    for y in 0..grid.size.height {
+        for x in 0..grid.size.width {
+            let point = Point::new(x, y);
+            if components.label(&point).is_some() && components.label(&point) != largest {
+                grid.set_block(&point, &GridBlock::Obstacle);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GridSize;
+    use crate::generation::Generator;
+    use crate::map_file;
+
+    fn generated(settings: CaveSettings, size: GridSize, seed: u64) -> GridMap {
+        let mut grid = GridMap::new(size);
+        grid.generate(&Generator::Cave(settings), seed);
+        grid
+    }
+
+    #[test]
+    fn a_seed_always_gives_the_same_cave() {
+        let size = GridSize::new(40, 20);
+        assert_eq!(
+            map_file::to_text(&generated(CaveSettings::new(), size, 7)),
+            map_file::to_text(&generated(CaveSettings::new(), size, 7))
+        );
+        assert_ne!(
+            map_file::to_text(&generated(CaveSettings::new(), size, 7)),
+            map_file::to_text(&generated(CaveSettings::new(), size, 8))
+        );
+    }
+
+    #[test]
+    fn caves_fill_the_grid_with_open_blocks_and_walls() {
+        for size in [
+            GridSize::new(1, 1),
+            GridSize::new(3, 40),
+            GridSize::new(100, 100),
+        ] {
+            let grid = generated(CaveSettings::new().set_remove_pockets(false), size, 3);
+            assert_eq!(grid.grid.len(), size.height as usize);
+            for row in &grid.grid {
+                assert_eq!(row.len(), size.width as usize);
+                assert!(row
+                    .iter()
+                    .all(|element| matches!(element.grid, GridBlock::Empty | GridBlock::Obstacle)));
+            }
+        }
+    }
+
+    #[test]
+    fn removing_pockets_leaves_one_open_area() {
+        for seed in 1..=10 {
+            let mut grid = generated(CaveSettings::new(), GridSize::new(40, 20), seed);
+            assert_eq!(Components::new(&grid).count(), 1, "seed {}", seed);
+            // joined orthogonally, so cutting corners can not split it either
+            grid.set_connectivity(Connectivity::EightWayNoCornerCutting);
+            assert_eq!(Components::new(&grid).count(), 1, "seed {}", seed);
+        }
+    }
+}
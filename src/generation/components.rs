@@ -0,0 +1,245 @@
+/*!
+ * Connected components of the passable blocks
+ * https://en.wikipedia.org/wiki/Component_(graph_theory)
+ *
+ * Two blocks are in the same component when one can be reached from the other with the grid's
+ * movement rules, so the start and end of a map can only be joined by a path when they share one.
+ * Maps where they do not can be repaired by carving through the fewest walls or by generating the
+ * map again from the following seeds.
+ */
+use super::fill;
+use crate::algorithms::{GridBlock, GridMap, Point};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+/**
+ * The number of seeds tried when regenerating a map until the start and end are connected
+ */
+pub const REGENERATE_ATTEMPTS: i32 = 100;
+
+/**
+ * Ways of joining a start and end that are not connected
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathRepair {
+    Carve,
+    Regenerate,
+}
+
+impl PathRepair {
+    pub const ALL: [PathRepair; 2] = [PathRepair::Carve, PathRepair::Regenerate];
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            PathRepair::Carve => "Carve a path",
+            PathRepair::Regenerate => "Regenerate the map",
+        }
+    }
+
+    pub fn to_id(&self) -> &str {
+        match self {
+            PathRepair::Carve => "carve",
+            PathRepair::Regenerate => "regenerate",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<PathRepair> {
+        PathRepair::ALL
+            .into_iter()
+            .find(|repair| repair.to_id() == id)
+    }
+}
+
+pub struct Components {
+    labels: Vec<Vec<Option<usize>>>,
+    sizes: Vec<usize>,
+}
+
+impl Components {
+    /**
+     * Labels every passable block with the index of its component, blocks are joined the same way
+     * the search algorithms move between them
+     */
+    pub fn new(grid: &GridMap) -> Components {
+        let mut labels = vec![vec![None; grid.size.width as usize]; grid.size.height as usize];
+        let mut sizes = Vec::new();
+        for y in 0..grid.size.height {
+            for x in 0..grid.size.width {
+                let point = Point::new(x, y);
+                if labels[y as usize][x as usize].is_some() || !grid.is_passable(&point) {
+                    continue;
+                }
+
+                let label = sizes.len();
+                let mut size = 0;
+                let mut stack = vec![point];
+                labels[y as usize][x as usize] = Some(label);
+                while let Some(current) = stack.pop() {
+                    size += 1;
+                    for (_, next) in grid.get_surrounding_blocks(&current) {
+                        if labels[next.y as usize][next.x as usize].is_none()
+                            && grid.is_passable(&next)
+                        {
+                            labels[next.y as usize][next.x as usize] = Some(label);
+                            stack.push(next);
+                        }
+                    }
+                }
+                sizes.push(size);
+            }
+        }
+        Components { labels, sizes }
+    }
+
+    /**
+     * The component of the point, impassable blocks have none
+     */
+    pub fn label(&self, point: &Point) -> Option<usize> {
+        self.labels[point.y as usize][point.x as usize]
+    }
+
+    pub fn connected(&self, a: &Point, b: &Point) -> bool {
+        match (self.label(a), self.label(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /**
+     * The number of blocks in the component
+     */
+    pub fn size(&self, label: usize) -> usize {
+        self.sizes[label]
+    }
+
+    /**
+     * The biggest component, the first one found on ties
+     */
+    pub fn largest(&self) -> Option<usize> {
+        (0..self.sizes.len()).min_by_key(|label| Reverse(self.sizes[*label]))
+    }
+}
+
+/**
+ * Clears the fewest impassable blocks needed to join the start and end and returns how many were
+ * cleared. The carved path only takes orthogonal steps so it can be walked with any movement rules.
+ * A carved map is no longer the one its seed generates, so the seed is dropped.
+ */
+pub fn carve_path(grid: &mut GridMap, start: &Point, end: &Point) -> usize {
+    let width = grid.size.width as usize;
+    let height = grid.size.height as usize;
+    let mut walls = vec![vec![usize::MAX; width]; height];
+    let mut parents: Vec<Vec<Option<Point>>> = vec![vec![None; width]; height];
+    let mut queue = VecDeque::new();
+    walls[start.y as usize][start.x as usize] = 0;
+    queue.push_back(*start);
+
+    // 0-1 breadth first search, open blocks are free to step on and walls cost one
+    while let Some(current) = queue.pop_front() {
+        if current == *end {
+            break;
+        }
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            let next = Point::new(current.x + dx, current.y + dy);
+            if !grid.contains(&next) {
+                continue;
+            }
+            let cost = if grid.is_passable(&next) { 0 } else { 1 };
+            let walls_to_next = walls[current.y as usize][current.x as usize] + cost;
+            if walls_to_next < walls[next.y as usize][next.x as usize] {
+                walls[next.y as usize][next.x as usize] = walls_to_next;
+                parents[next.y as usize][next.x as usize] = Some(current);
+                if cost == 0 {
+                    queue.push_front(next);
+                } else {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let mut carved = 0;
+    let mut current = *end;
+    while let Some(parent) = parents[current.y as usize][current.x as usize] {
+        if !grid.is_passable(&current) {
+            grid.set_block(&current, &GridBlock::Empty);
+            carved += 1;
+        }
+        current = parent;
+    }
+    if carved > 0 {
+        grid.clear_generation();
+    }
+    carved
+}
+
+/**
+ * Generates the map again from the seeds after its own until the start and end are connected,
+ * keeping them where they are. Painted terrain is lost. Returns false for maps that were not generated and when no seed
+ * within the attempts connects them.
+ */
+pub fn regenerate_until_connected(grid: &mut GridMap) -> bool {
+    let (generator, mut seed) = match (grid.generator, grid.seed) {
+        (Some(generator), Some(seed)) => (generator, seed),
+        _ => return false,
+    };
+
+    let (start, end) = (grid.start, grid.end);
+    for _ in 0..REGENERATE_ATTEMPTS {
+        if Components::new(grid).connected(&start, &end) {
+            return true;
+        }
+        seed = seed.wrapping_add(1);
+        // Obstacles are scattered over what is already there, so start from an empty map
+        fill(grid, GridBlock::Empty);
+        grid.generate(&generator, seed);
+        grid.place_endpoints(&start, &end);
+    }
+    Components::new(grid).connected(&start, &end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GridSize;
+    use crate::display::setup::MapCrowding;
+    use crate::generation::Generator;
+    use crate::map_file;
+
+    fn generated(seed: u64) -> GridMap {
+        let mut grid = GridMap::new(GridSize::new(20, 10));
+        grid.generate(&Generator::Obstacles(MapCrowding::High), seed);
+        grid.place_endpoints(&Point::new(0, 0), &Point::new(19, 9));
+        grid
+    }
+
+    #[test]
+    fn a_seed_always_gives_the_same_map() {
+        assert_eq!(
+            map_file::to_text(&generated(7)),
+            map_file::to_text(&generated(7))
+        );
+        assert_ne!(
+            map_file::to_text(&generated(7)),
+            map_file::to_text(&generated(8))
+        );
+    }
+
+    #[test]
+    fn carving_connects_the_endpoints_and_forgets_the_seed() {
+        let mut grid = generated(7);
+        for y in 0..grid.size.height {
+            grid.set_block(&Point::new(10, y), &GridBlock::Obstacle);
+        }
+        let (start, end) = (grid.start, grid.end);
+
+        assert!(carve_path(&mut grid, &start, &end) > 0);
+        assert!(Components::new(&grid).connected(&start, &end));
+        assert_eq!(grid.seed, None);
+        assert!(grid.generator.is_none());
+    }
+}
@@ -0,0 +1,371 @@
+/*!
+ * Rooms and corridors dungeons
+ *
+ * Rooms are carved out of solid rock either by binary space partitioning, where the map is
+ * split in two until the parts are room sized and sibling parts are joined by a corridor, or by
+ * placing rooms at random and joining each one to the room placed before it.
+ * Corridors bend once, so they can always be walked with 4-way movement.
+ */
+use super::fill;
+use crate::algorithms::{GridBlock, GridMap, Point};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/**
+ * The number of rooms tried when placing them at random, most get rejected for overlapping
+ */
+const PLACEMENT_ATTEMPTS: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomLayout {
+    BinarySpacePartition,
+    Random,
+}
+
+impl RoomLayout {
+    pub const ALL: [RoomLayout; 2] = [RoomLayout::BinarySpacePartition, RoomLayout::Random];
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            RoomLayout::BinarySpacePartition => "Binary space partitioning",
+            RoomLayout::Random => "Random placement",
+        }
+    }
+
+    pub fn to_id(&self) -> &str {
+        match self {
+            RoomLayout::BinarySpacePartition => "bsp",
+            RoomLayout::Random => "random",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<RoomLayout> {
+        RoomLayout::ALL
+            .into_iter()
+            .find(|layout| layout.to_id() == id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DungeonSettings {
+    pub layout: RoomLayout,
+    pub min_room_size: i32,
+    pub max_room_size: i32,
+    pub place_endpoints: bool,
+}
+
+impl Default for DungeonSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DungeonSettings {
+    pub fn new() -> DungeonSettings {
+        DungeonSettings {
+            layout: RoomLayout::BinarySpacePartition,
+            min_room_size: 3,
+            max_room_size: 8,
+            place_endpoints: true,
+        }
+    }
+
+    pub fn set_layout(mut self, layout: RoomLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn set_room_size(mut self, min_room_size: i32, max_room_size: i32) -> Self {
+        self.min_room_size = min_room_size;
+        self.max_room_size = max_room_size.max(min_room_size);
+        self
+    }
+
+    /**
+     * Puts the start and end in two different rooms
+     */
+    pub fn set_place_endpoints(mut self, place_endpoints: bool) -> Self {
+        self.place_endpoints = place_endpoints;
+        self
+    }
+}
+
+/**
+ * A rectangle of blocks, used for both the rooms and the parts of the map they are placed in
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Room {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Room {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Room {
+        Room {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    pub fn random_point(&self, rng: &mut impl Rng) -> Point {
+        Point::new(
+            rng.gen_range(self.x..self.x + self.width),
+            rng.gen_range(self.y..self.y + self.height),
+        )
+    }
+
+    /**
+     * Whether the rooms overlap or touch, rooms need a wall between them
+     */
+    fn touches(&self, other: &Room) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+}
+
+/**
+ * Replaces the grid with rooms joined by corridors and returns the rooms
+ */
+pub fn generate(grid: &mut GridMap, settings: &DungeonSettings, rng: &mut impl Rng) -> Vec<Room> {
+    fill(grid, GridBlock::Obstacle);
+    let mut rooms = Vec::new();
+    match settings.layout {
+        RoomLayout::BinarySpacePartition => {
+            let map = Room::new(0, 0, grid.size.width, grid.size.height);
+            partition(grid, &map, settings, rng, &mut rooms);
+        }
+        RoomLayout::Random => place_randomly(grid, settings, rng, &mut rooms),
+    }
+
+    if settings.place_endpoints && rooms.len() >= 2 {
+        let picked: Vec<&Room> = rooms.choose_multiple(rng, 2).collect();
+        let start = picked[0].random_point(rng);
+        let end = picked[1].random_point(rng);
+        grid.set_block(&start, &GridBlock::Start);
+        grid.set_start(&start);
+        grid.set_block(&end, &GridBlock::End);
+        grid.set_end(&end);
+    }
+    rooms
+}
+
+fn carve_room(grid: &mut GridMap, room: &Room) {
+    for y in room.y..room.y + room.height {
+        for x in room.x..room.x + room.width {
+            grid.set_block(&Point::new(x, y), &GridBlock::Empty);
+        }
+    }
+}
+
+/**
+ * Carves a corridor between the points, going horizontally or vertically first at random
+ */
+fn carve_corridor(grid: &mut GridMap, from: &Point, to: &Point, rng: &mut impl Rng) {
+    let corner = if rng.gen_bool(0.5) {
+        Point::new(to.x, from.y)
+    } else {
+        Point::new(from.x, to.y)
+    };
+    for (a, b) in [(from, &corner), (&corner, to)] {
+        for y in a.y.min(b.y)..=a.y.max(b.y) {
+            for x in a.x.min(b.x)..=a.x.max(b.x) {
+                grid.set_block(&Point::new(x, y), &GridBlock::Empty);
+            }
+        }
+    }
+}
+
+/**
+ * Splits the area until it fits a room, every split is bridged by a corridor between a room on
+ * each side so the whole dungeon stays connected
+ */
+fn partition(
+    grid: &mut GridMap,
+    area: &Room,
+    settings: &DungeonSettings,
+    rng: &mut impl Rng,
+    rooms: &mut Vec<Room>,
+) {
+    // A room needs a wall on each side
+    let min_area = settings.min_room_size + 2;
+    let max_area = settings.max_room_size + 2;
+    let can_split_width = area.width >= min_area * 2;
+    let can_split_height = area.height >= min_area * 2;
+    let fits_room = area.width <= max_area && area.height <= max_area;
+
+    if (!can_split_width && !can_split_height) || (fits_room && rng.gen_bool(0.5)) {
+        if area.width < min_area || area.height < min_area {
+            return;
+        }
+        let width =
+            rng.gen_range(settings.min_room_size..=settings.max_room_size.min(area.width - 2));
+        let height =
+            rng.gen_range(settings.min_room_size..=settings.max_room_size.min(area.height - 2));
+        let room = Room::new(
+            area.x + 1 + rng.gen_range(0..=area.width - 2 - width),
+            area.y + 1 + rng.gen_range(0..=area.height - 2 - height),
+            width,
+            height,
+        );
+        carve_room(grid, &room);
+        rooms.push(room);
+        return;
+    }
+
+    let split_width = match (can_split_width, can_split_height) {
+        (true, false) => true,
+        (false, true) => false,
+        _ if area.width * 4 > area.height * 5 => true,
+        _ if area.height * 4 > area.width * 5 => false,
+        _ => rng.gen_bool(0.5),
+    };
+    let (first, second) = if split_width {
+        let split = rng.gen_range(min_area..=area.width - min_area);
+        (
+            Room::new(area.x, area.y, split, area.height),
+            Room::new(area.x + split, area.y, area.width - split, area.height),
+        )
+    } else {
+        let split = rng.gen_range(min_area..=area.height - min_area);
+        (
+            Room::new(area.x, area.y, area.width, split),
+            Room::new(area.x, area.y + split, area.width, area.height - split),
+        )
+    };
+
+    let first_rooms = rooms.len();
+    partition(grid, &first, settings, rng, rooms);
+    let second_rooms = rooms.len();
+    partition(grid, &second, settings, rng, rooms);
+
+    if first_rooms < second_rooms && second_rooms < rooms.len() {
+        let from = rooms[rng.gen_range(first_rooms..second_rooms)].center();
+        let to = rooms[rng.gen_range(second_rooms..rooms.len())].center();
+        carve_corridor(grid, &from, &to, rng);
+    }
+}
+
+fn place_randomly(
+    grid: &mut GridMap,
+    settings: &DungeonSettings,
+    rng: &mut impl Rng,
+    rooms: &mut Vec<Room>,
+) {
+    for _ in 0..PLACEMENT_ATTEMPTS {
+        let max_width = settings.max_room_size.min(grid.size.width - 2);
+        let max_height = settings.max_room_size.min(grid.size.height - 2);
+        if max_width < settings.min_room_size || max_height < settings.min_room_size {
+            return;
+        }
+
+        let width = rng.gen_range(settings.min_room_size..=max_width);
+        let height = rng.gen_range(settings.min_room_size..=max_height);
+        let room = Room::new(
+            rng.gen_range(1..=grid.size.width - 1 - width),
+            rng.gen_range(1..=grid.size.height - 1 - height),
+            width,
+            height,
+        );
+        if rooms.iter().any(|other| room.touches(other)) {
+            continue;
+        }
+
+        carve_room(grid, &room);
+        if let Some(previous) = rooms.last() {
+            carve_corridor(grid, &previous.center(), &room.center(), rng);
+        }
+        rooms.push(room);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GridSize;
+    use crate::generation::components::Components;
+    use crate::generation::Generator;
+    use crate::map_file;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn generated(settings: &DungeonSettings, size: GridSize, seed: u64) -> (GridMap, Vec<Room>) {
+        let mut grid = GridMap::new(size);
+        let rooms = generate(&mut grid, settings, &mut ChaCha8Rng::seed_from_u64(seed));
+        (grid, rooms)
+    }
+
+    fn contains(room: &Room, point: &Point) -> bool {
+        (room.x..room.x + room.width).contains(&point.x)
+            && (room.y..room.y + room.height).contains(&point.y)
+    }
+
+    #[test]
+    fn a_seed_always_gives_the_same_dungeon() {
+        for layout in RoomLayout::ALL {
+            let text = |seed| {
+                let mut grid = GridMap::new(GridSize::new(40, 20));
+                grid.generate(
+                    &Generator::Dungeon(DungeonSettings::new().set_layout(layout)),
+                    seed,
+                );
+                map_file::to_text(&grid)
+            };
+            assert_eq!(text(7), text(7), "{}", layout.to_name());
+            assert_ne!(text(7), text(8), "{}", layout.to_name());
+        }
+    }
+
+    #[test]
+    fn rooms_stay_inside_the_walls_without_touching() {
+        let size = GridSize::new(60, 30);
+        for layout in RoomLayout::ALL {
+            let settings = DungeonSettings::new().set_layout(layout);
+            for seed in 1..=10 {
+                let (_, rooms) = generated(&settings, size, seed);
+                assert!(rooms.len() >= 2, "{} seed {}", layout.to_name(), seed);
+                for (i, room) in rooms.iter().enumerate() {
+                    assert!(room.x >= 1 && room.y >= 1);
+                    assert!(room.x + room.width < size.width);
+                    assert!(room.y + room.height < size.height);
+                    assert!((3..=8).contains(&room.width) && (3..=8).contains(&room.height));
+                    assert!(rooms[i + 1..].iter().all(|other| !room.touches(other)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_room_is_reached_from_the_start() {
+        for layout in RoomLayout::ALL {
+            let settings = DungeonSettings::new().set_layout(layout);
+            for seed in 1..=10 {
+                let (grid, rooms) = generated(&settings, GridSize::new(60, 30), seed);
+                let components = Components::new(&grid);
+                assert_eq!(components.count(), 1, "{} seed {}", layout.to_name(), seed);
+                assert!(rooms.iter().any(|room| contains(room, &grid.start)));
+                assert!(rooms.iter().any(|room| contains(room, &grid.end)));
+                assert!(components.connected(&grid.start, &grid.end));
+            }
+        }
+    }
+
+    #[test]
+    fn maps_too_small_for_a_room_stay_solid() {
+        for layout in RoomLayout::ALL {
+            let settings = DungeonSettings::new().set_layout(layout);
+            let (grid, rooms) = generated(&settings, GridSize::new(4, 4), 1);
+            assert!(rooms.is_empty());
+            assert_eq!(Components::new(&grid).count(), 0);
+        }
+    }
+}
@@ -0,0 +1,484 @@
+/*!
+ * Maze generators
+ * https://en.wikipedia.org/wiki/Maze_generation_algorithm
+ *
+ * Maze cells sit on even coordinates and the blocks between two cells are walls that get carved
+ * into passages, so a grid with an even width or height keeps an obstacle border on that side.
+ */
+use super::fill;
+use crate::algorithms::{GridBlock, GridMap, Point};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    Eller,
+    RecursiveDivision,
+}
+
+impl MazeAlgorithm {
+    pub const ALL: [MazeAlgorithm; 6] = [
+        MazeAlgorithm::RecursiveBacktracker,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::Wilson,
+        MazeAlgorithm::Eller,
+        MazeAlgorithm::RecursiveDivision,
+    ];
+
+    pub fn to_name(&self) -> &str {
+        match self {
+            MazeAlgorithm::RecursiveBacktracker => "Recursive Backtracker",
+            MazeAlgorithm::Prim => "Prim's",
+            MazeAlgorithm::Kruskal => "Kruskal's",
+            MazeAlgorithm::Wilson => "Wilson's",
+            MazeAlgorithm::Eller => "Eller's",
+            MazeAlgorithm::RecursiveDivision => "Recursive Division",
+        }
+    }
+
+    pub fn to_id(&self) -> &str {
+        match self {
+            MazeAlgorithm::RecursiveBacktracker => "backtracker",
+            MazeAlgorithm::Prim => "prim",
+            MazeAlgorithm::Kruskal => "kruskal",
+            MazeAlgorithm::Wilson => "wilson",
+            MazeAlgorithm::Eller => "eller",
+            MazeAlgorithm::RecursiveDivision => "division",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<MazeAlgorithm> {
+        MazeAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.to_id() == id)
+    }
+}
+
+/**
+ * The size of the maze in cells
+ */
+struct Cells {
+    width: i32,
+    height: i32,
+}
+
+impl Cells {
+    fn new(grid: &GridMap) -> Cells {
+        Cells {
+            width: (grid.size.width + 1) / 2,
+            height: (grid.size.height + 1) / 2,
+        }
+    }
+
+    fn all(&self) -> Vec<Point> {
+        let mut cells = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                cells.push(Point::new(x, y));
+            }
+        }
+        cells
+    }
+
+    fn random(&self, rng: &mut impl Rng) -> Point {
+        Point::new(rng.gen_range(0..self.width), rng.gen_range(0..self.height))
+    }
+
+    fn neighbors(&self, cell: &Point) -> Vec<Point> {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .map(|(dx, dy)| Point::new(cell.x + dx, cell.y + dy))
+            .filter(|n| n.x >= 0 && n.y >= 0 && n.x < self.width && n.y < self.height)
+            .collect()
+    }
+
+    fn visited(&self) -> Vec<Vec<bool>> {
+        vec![vec![false; self.width as usize]; self.height as usize]
+    }
+}
+
+fn carve_cell(grid: &mut GridMap, cell: &Point) {
+    grid.set_block(&Point::new(cell.x * 2, cell.y * 2), &GridBlock::Empty);
+}
+
+/**
+ * Opens both cells and the wall between them
+ */
+fn carve(grid: &mut GridMap, from: &Point, to: &Point) {
+    carve_cell(grid, from);
+    carve_cell(grid, to);
+    grid.set_block(&Point::new(from.x + to.x, from.y + to.y), &GridBlock::Empty);
+}
+
+/**
+ * Replaces the whole grid with a perfect maze, every cell can reach every other cell by exactly one path
+ */
+pub fn generate(grid: &mut GridMap, algorithm: MazeAlgorithm, rng: &mut impl Rng) {
+    fill(grid, GridBlock::Obstacle);
+    let cells = Cells::new(grid);
+    match algorithm {
+        MazeAlgorithm::RecursiveBacktracker => recursive_backtracker(grid, &cells, rng),
+        MazeAlgorithm::Prim => prim(grid, &cells, rng),
+        MazeAlgorithm::Kruskal => kruskal(grid, &cells, rng),
+        MazeAlgorithm::Wilson => wilson(grid, &cells, rng),
+        MazeAlgorithm::Eller => eller(grid, &cells, rng),
+        MazeAlgorithm::RecursiveDivision => {
+            for cell in cells.all() {
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let point = Point::new(cell.x * 2 + dx, cell.y * 2 + dy);
+                    if point.x < cells.width * 2 - 1 && point.y < cells.height * 2 - 1 {
+                        grid.set_block(&point, &GridBlock::Empty);
+                    }
+                }
+            }
+            recursive_division(grid, 0, 0, cells.width, cells.height, rng);
+        }
+    }
+}
+
+/**
+ * Knocks out each wall separating two passages with the given chance, adding loops to a perfect maze
+ */
+pub fn add_loops(grid: &mut GridMap, chance: f64, rng: &mut impl Rng) {
+    for y in 0..grid.size.height {
+        for x in 0..grid.size.width {
+            let point = Point::new(x, y);
+            if (x + y) % 2 == 0 || grid.get_block(&point) != &GridBlock::Obstacle {
+                continue;
+            }
+
+            let (a, b) = if x % 2 == 1 {
+                (Point::new(x - 1, y), Point::new(x + 1, y))
+            } else {
+                (Point::new(x, y - 1), Point::new(x, y + 1))
+            };
+            let separates_passages = [a, b]
+                .iter()
+                .all(|side| grid.contains(side) && grid.get_cost(side).is_some());
+            if separates_passages && rng.gen_bool(chance) {
+                grid.set_block(&point, &GridBlock::Empty);
+            }
+        }
+    }
+}
+
+/**
+ * Walks to random unvisited neighbours, backtracking along the walk when stuck
+ */
+fn recursive_backtracker(grid: &mut GridMap, cells: &Cells, rng: &mut impl Rng) {
+    let mut visited = cells.visited();
+    let first = cells.random(rng);
+    visited[first.y as usize][first.x as usize] = true;
+    carve_cell(grid, &first);
+
+    let mut stack = vec![first];
+    while let Some(current) = stack.last().copied() {
+        let unvisited: Vec<Point> = cells
+            .neighbors(&current)
+            .into_iter()
+            .filter(|n| !visited[n.y as usize][n.x as usize])
+            .collect();
+        match unvisited.choose(rng) {
+            Some(next) => {
+                visited[next.y as usize][next.x as usize] = true;
+                carve(grid, &current, next);
+                stack.push(*next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
+
+/**
+ * Grows the maze from one cell by opening a random wall on its border each step
+ */
+fn prim(grid: &mut GridMap, cells: &Cells, rng: &mut impl Rng) {
+    let mut in_maze = cells.visited();
+    let first = cells.random(rng);
+    in_maze[first.y as usize][first.x as usize] = true;
+    carve_cell(grid, &first);
+
+    let mut walls: Vec<(Point, Point)> = cells
+        .neighbors(&first)
+        .into_iter()
+        .map(|n| (first, n))
+        .collect();
+    while !walls.is_empty() {
+        let (from, to) = walls.swap_remove(rng.gen_range(0..walls.len()));
+        if in_maze[to.y as usize][to.x as usize] {
+            continue;
+        }
+        in_maze[to.y as usize][to.x as usize] = true;
+        carve(grid, &from, &to);
+        for n in cells.neighbors(&to) {
+            if !in_maze[n.y as usize][n.x as usize] {
+                walls.push((to, n));
+            }
+        }
+    }
+}
+
+/**
+ * Opens walls in a random order whenever the cells on either side are not connected yet
+ */
+fn kruskal(grid: &mut GridMap, cells: &Cells, rng: &mut impl Rng) {
+    let index = |cell: &Point| (cell.y * cells.width + cell.x) as usize;
+    let mut walls = Vec::new();
+    for cell in cells.all() {
+        for n in cells.neighbors(&cell) {
+            if index(&n) > index(&cell) {
+                walls.push((cell, n));
+            }
+        }
+        carve_cell(grid, &cell);
+    }
+    walls.shuffle(rng);
+
+    let mut parent: Vec<usize> = (0..(cells.width * cells.height) as usize).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for (a, b) in walls {
+        let root_a = find(&mut parent, index(&a));
+        let root_b = find(&mut parent, index(&b));
+        if root_a != root_b {
+            parent[root_a] = root_b;
+            carve(grid, &a, &b);
+        }
+    }
+}
+
+/**
+ * Adds loop-erased random walks that end on the maze, giving an unbiased maze
+ *
+ * Every cell on a walk remembers the direction it was last left in, so following those
+ * directions after the walk skips any loops it made
+ */
+fn wilson(grid: &mut GridMap, cells: &Cells, rng: &mut impl Rng) {
+    let mut in_maze = cells.visited();
+    let first = cells.random(rng);
+    in_maze[first.y as usize][first.x as usize] = true;
+    carve_cell(grid, &first);
+
+    let mut next: Vec<Vec<Option<Point>>> =
+        vec![vec![None; cells.width as usize]; cells.height as usize];
+    let mut order = cells.all();
+    order.shuffle(rng);
+    for cell in order {
+        let mut current = cell;
+        while !in_maze[current.y as usize][current.x as usize] {
+            let step = *cells
+                .neighbors(&current)
+                .choose(rng)
+                .expect("a maze with more than one cell has neighbours");
+            next[current.y as usize][current.x as usize] = Some(step);
+            current = step;
+        }
+
+        let mut current = cell;
+        while !in_maze[current.y as usize][current.x as usize] {
+            in_maze[current.y as usize][current.x as usize] = true;
+            let step = next[current.y as usize][current.x as usize]
+                .expect("every cell on the walk has a next step");
+            carve(grid, &current, &step);
+            current = step;
+        }
+    }
+}
+
+/**
+ * Builds the maze one row at a time, only remembering which set each cell of the current row belongs to
+ */
+fn eller(grid: &mut GridMap, cells: &Cells, rng: &mut impl Rng) {
+    let mut sets: Vec<Option<usize>> = vec![None; cells.width as usize];
+    let mut next_set = 0;
+
+    for y in 0..cells.height {
+        for (x, set) in sets.iter_mut().enumerate() {
+            if set.is_none() {
+                *set = Some(next_set);
+                next_set += 1;
+            }
+            carve_cell(grid, &Point::new(x as i32, y));
+        }
+
+        let last_row = y == cells.height - 1;
+        for x in 0..sets.len() - 1 {
+            if sets[x] != sets[x + 1] && (last_row || rng.gen_bool(0.5)) {
+                let (kept, merged) = (sets[x], sets[x + 1]);
+                for set in sets.iter_mut().filter(|set| **set == merged) {
+                    *set = kept;
+                }
+                carve(grid, &Point::new(x as i32, y), &Point::new(x as i32 + 1, y));
+            }
+        }
+        if last_row {
+            break;
+        }
+
+        let mut below = vec![None; sets.len()];
+        let mut seen = Vec::new();
+        for set in sets.iter().flatten() {
+            if seen.contains(set) {
+                continue;
+            }
+            seen.push(*set);
+
+            let mut members: Vec<usize> =
+                (0..sets.len()).filter(|x| sets[*x] == Some(*set)).collect();
+            members.shuffle(rng);
+            let count = rng.gen_range(1..=members.len());
+            for x in &members[..count] {
+                below[*x] = Some(*set);
+                carve(
+                    grid,
+                    &Point::new(*x as i32, y),
+                    &Point::new(*x as i32, y + 1),
+                );
+            }
+        }
+        sets = below;
+    }
+}
+
+/**
+ * Splits the open chamber with a wall that has a single gap, then divides both halves the same way
+ *
+ * The chamber is given in cells
+ */
+fn recursive_division(
+    grid: &mut GridMap,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    rng: &mut impl Rng,
+) {
+    if width < 2 || height < 2 {
+        return;
+    }
+
+    let horizontal = match width.cmp(&height) {
+        std::cmp::Ordering::Less => true,
+        std::cmp::Ordering::Greater => false,
+        std::cmp::Ordering::Equal => rng.gen_bool(0.5),
+    };
+
+    if horizontal {
+        let wall = rng.gen_range(y..y + height - 1);
+        let gap = rng.gen_range(x..x + width);
+        for wall_x in x * 2..(x + width) * 2 - 1 {
+            if wall_x != gap * 2 {
+                grid.set_block(&Point::new(wall_x, wall * 2 + 1), &GridBlock::Obstacle);
+            }
+        }
+        recursive_division(grid, x, y, width, wall - y + 1, rng);
+        recursive_division(grid, x, wall + 1, width, y + height - wall - 1, rng);
+    } else {
+        let wall = rng.gen_range(x..x + width - 1);
+        let gap = rng.gen_range(y..y + height);
+        for wall_y in y * 2..(y + height) * 2 - 1 {
+            if wall_y != gap * 2 {
+                grid.set_block(&Point::new(wall * 2 + 1, wall_y), &GridBlock::Obstacle);
+            }
+        }
+        recursive_division(grid, x, y, wall - x + 1, height, rng);
+        recursive_division(grid, wall + 1, y, x + width - wall - 1, height, rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GridSize;
+    use crate::generation::components::Components;
+    use crate::generation::Generator;
+    use crate::map_file;
+
+    fn generated(algorithm: MazeAlgorithm, loop_chance: f64, size: GridSize, seed: u64) -> GridMap {
+        let mut grid = GridMap::new(size);
+        grid.generate(
+            &Generator::Maze {
+                algorithm,
+                loop_chance,
+            },
+            seed,
+        );
+        grid
+    }
+
+    fn open_blocks(grid: &GridMap) -> usize {
+        (0..grid.size.height)
+            .flat_map(|y| (0..grid.size.width).map(move |x| Point::new(x, y)))
+            .filter(|point| grid.is_passable(point))
+            .count()
+    }
+
+    #[test]
+    fn a_seed_always_gives_the_same_maze() {
+        for algorithm in MazeAlgorithm::ALL {
+            let size = GridSize::new(21, 11);
+            assert_eq!(
+                map_file::to_text(&generated(algorithm, 0.2, size, 7)),
+                map_file::to_text(&generated(algorithm, 0.2, size, 7)),
+                "{}",
+                algorithm.to_name()
+            );
+        }
+    }
+
+    #[test]
+    fn every_passage_joins_every_other_by_one_path() {
+        for algorithm in MazeAlgorithm::ALL {
+            for size in [
+                GridSize::new(21, 11),
+                GridSize::new(20, 10),
+                GridSize::new(1, 1),
+            ] {
+                for seed in 1..=5 {
+                    let grid = generated(algorithm, 0.0, size, seed);
+                    assert_eq!(grid.grid.len(), size.height as usize);
+                    assert!(grid.grid.iter().all(|row| row.len() == size.width as usize));
+
+                    let components = Components::new(&grid);
+                    assert_eq!(components.count(), 1, "{}", algorithm.to_name());
+                    // a tree of cells has one passage less between them than it has cells
+                    let cells = Cells::new(&grid);
+                    assert_eq!(
+                        open_blocks(&grid) as i32,
+                        cells.width * cells.height * 2 - 1,
+                        "{}",
+                        algorithm.to_name()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn loops_keep_the_maze_connected() {
+        for algorithm in MazeAlgorithm::ALL {
+            let size = GridSize::new(21, 11);
+            let perfect = generated(algorithm, 0.0, size, 3);
+            let looped = generated(algorithm, 1.0, size, 3);
+            assert_eq!(Components::new(&looped).count(), 1);
+            assert!(
+                open_blocks(&looped) > open_blocks(&perfect),
+                "{}",
+                algorithm.to_name()
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+/*!
+ * Generators that lay out a whole map, as opposed to `GridMap::generate_obstacles` which
+ * scatters obstacles over what is already there
+ */
+pub mod cave;
+pub mod components;
+pub mod dungeon;
+pub mod maze;
+pub mod terrain;
+
+use crate::algorithms::{GridBlock, GridMap, Point};
+use crate::display::setup::MapCrowding;
+use cave::CaveSettings;
+use dungeon::DungeonSettings;
+use maze::MazeAlgorithm;
+use rand::Rng;
+use terrain::TerrainSettings;
+
+/**
+ * A generator together with its settings, with a seed it generates the same map again, see `GridMap::generate`
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Generator {
+    Obstacles(MapCrowding),
+    Maze {
+        algorithm: MazeAlgorithm,
+        loop_chance: f64,
+    },
+    Cave(CaveSettings),
+    Dungeon(DungeonSettings),
+    Terrain(TerrainSettings),
+}
+
+impl Generator {
+    pub fn generate(&self, grid: &mut GridMap, rng: &mut impl Rng) {
+        match self {
+            Generator::Obstacles(crowding) => grid.generate_obstacles(*crowding, rng),
+            Generator::Maze {
+                algorithm,
+                loop_chance,
+            } => grid.generate_maze(*algorithm, *loop_chance, rng),
+            Generator::Cave(settings) => grid.generate_cave(settings, rng),
+            Generator::Dungeon(settings) => {
+                grid.generate_dungeon(settings, rng);
+            }
+            Generator::Terrain(settings) => grid.generate_terrain(settings, rng),
+        }
+    }
+}
+
+/**
+ * A fresh seed for when the user does not pick one
+ */
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+/**
+ * Sets every block of the grid to the given block
+ */
+pub fn fill(grid: &mut GridMap, block: GridBlock) {
+    for y in 0..grid.size.height {
+        for x in 0..grid.size.width {
+            grid.set_block(&Point::new(x, y), &block);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+/*!
+ * Weighted terrain from value noise
+ * https://en.wikipedia.org/wiki/Value_noise
+ *
+ * An elevation map is built from several octaves of value noise and cut into bands, from deep
+ * lakes through water, sand, plains and grass up to hills and impassable peaks.
+ * Rivers follow the line where a second noise map crosses its midpoint, with mud along the banks.
+ */
+use crate::algorithms::{GridBlock, GridMap, Point};
+use rand::Rng;
+
+/**
+ * Elevation bands, each block is the first band its elevation is below
+ */
+const ELEVATION_BANDS: [(f64, GridBlock); 6] = [
+    (0.18, GridBlock::DeepWater),
+    (0.26, GridBlock::Water),
+    (0.32, GridBlock::Sand),
+    (0.55, GridBlock::Empty),
+    (0.70, GridBlock::Grass),
+    (0.85, GridBlock::Hill),
+];
+
+/**
+ * Half the width of a river and of a river with its banks, in noise units
+ */
+const RIVER_WIDTH: f64 = 0.03;
+const BANK_WIDTH: f64 = 0.06;
+
+/**
+ * The most noise layers added together, enough to reach single blocks at scales up to 128
+ */
+pub const MAX_OCTAVES: i32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainSettings {
+    pub scale: f64,
+    pub octaves: i32,
+    pub rivers: bool,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TerrainSettings {
+    pub fn new() -> TerrainSettings {
+        TerrainSettings {
+            scale: 16.0,
+            octaves: 4,
+            rivers: true,
+        }
+    }
+
+    /**
+     * The size in blocks of the largest hills and lakes
+     */
+    pub fn set_scale(mut self, scale: f64) -> Self {
+        self.scale = scale.max(1.0);
+        self
+    }
+
+    /**
+     * The number of noise layers added together, each one half the size of the one before
+     */
+    pub fn set_octaves(mut self, octaves: i32) -> Self {
+        self.octaves = octaves.clamp(1, MAX_OCTAVES);
+        self
+    }
+
+    pub fn set_rivers(mut self, rivers: bool) -> Self {
+        self.rivers = rivers;
+        self
+    }
+}
+
+/**
+ * Random values on a lattice, read between the lattice points by smooth interpolation
+ */
+struct ValueNoise {
+    lattice: Vec<Vec<f64>>,
+    frequency: f64,
+}
+
+impl ValueNoise {
+    fn new(size: (i32, i32), frequency: f64, rng: &mut impl Rng) -> ValueNoise {
+        let width = (size.0 as f64 * frequency) as usize + 2;
+        let height = (size.1 as f64 * frequency) as usize + 2;
+        ValueNoise {
+            lattice: (0..height)
+                .map(|_| (0..width).map(|_| rng.gen_range(0.0..1.0)).collect())
+                .collect(),
+            frequency,
+        }
+    }
+
+    fn sample(&self, x: i32, y: i32) -> f64 {
+        let fx = x as f64 * self.frequency;
+        let fy = y as f64 * self.frequency;
+        let (x0, y0) = (fx.floor() as usize, fy.floor() as usize);
+        let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+        let (tx, ty) = (smooth(fx.fract()), smooth(fy.fract()));
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let top = lerp(self.lattice[y0][x0], self.lattice[y0][x0 + 1], tx);
+        let bottom = lerp(self.lattice[y0 + 1][x0], self.lattice[y0 + 1][x0 + 1], tx);
+        lerp(top, bottom, ty)
+    }
+}
+
+/**
+ * Adds the octaves together and stretches the result over the whole 0 to 1 range, so the
+ * elevation bands cover the same share of every map. Octaves finer than a block only add noise
+ * and are left out.
+ */
+fn fractal_noise(grid: &GridMap, settings: &TerrainSettings, rng: &mut impl Rng) -> Vec<Vec<f64>> {
+    let size = (grid.size.width, grid.size.height);
+    let octaves: Vec<(ValueNoise, f64)> = (0..settings.octaves)
+        .map(|octave| (2f64.powi(octave) / settings.scale, 0.5f64.powi(octave)))
+        .take_while(|(frequency, _)| *frequency <= 1.0)
+        .map(|(frequency, amplitude)| (ValueNoise::new(size, frequency, rng), amplitude))
+        .collect();
+
+    let mut noise: Vec<Vec<f64>> = (0..grid.size.height)
+        .map(|y| {
+            (0..grid.size.width)
+                .map(|x| {
+                    octaves
+                        .iter()
+                        .map(|(layer, amplitude)| layer.sample(x, y) * amplitude)
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+
+    let min = noise
+        .iter()
+        .flatten()
+        .cloned()
+        .fold(f64::INFINITY, f64::min);
+    let max = noise
+        .iter()
+        .flatten()
+        .cloned()
+        .fold(f64::NEG_INFINITY, f64::max);
+    let range = (max - min).max(f64::EPSILON);
+    for value in noise.iter_mut().flatten() {
+        *value = (*value - min) / range;
+    }
+    noise
+}
+
+pub fn generate(grid: &mut GridMap, settings: &TerrainSettings, rng: &mut impl Rng) {
+    let elevation = fractal_noise(grid, settings, rng);
+    let rivers = settings
+        .rivers
+        .then(|| fractal_noise(grid, &settings.set_octaves(settings.octaves.min(2)), rng));
+
+    for y in 0..grid.size.height {
+        for x in 0..grid.size.width {
+            let height = elevation[y as usize][x as usize];
+            let mut block = ELEVATION_BANDS
+                .iter()
+                .find(|(limit, _)| height < *limit)
+                .map_or(GridBlock::Obstacle, |(_, block)| *block);
+
+            if let Some(rivers) = &rivers {
+                let distance = (rivers[y as usize][x as usize] - 0.5).abs();
+                let on_land = height >= ELEVATION_BANDS[1].0 && block != GridBlock::Obstacle;
+                if on_land && distance < RIVER_WIDTH {
+                    block = GridBlock::DeepWater;
+                } else if on_land && distance < BANK_WIDTH {
+                    block = GridBlock::Mud;
+                }
+            }
+            grid.set_block(&Point::new(x, y), &block);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GridSize;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn octaves_stay_coarser_than_a_block() {
+        let settings = TerrainSettings::new().set_scale(1.0).set_octaves(i32::MAX);
+        assert_eq!(settings.octaves, MAX_OCTAVES);
+        let mut grid = GridMap::new(GridSize::new(100, 100));
+        let noise = fractal_noise(&grid, &settings, &mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(noise.len(), 100);
+        generate(&mut grid, &settings, &mut ChaCha8Rng::seed_from_u64(1));
+    }
+}
@@ -1,34 +1,64 @@
 pub mod algorithms;
+pub mod cli;
 pub mod display;
+pub mod generation;
+pub mod map_file;
 pub mod tui;
 
-use crate::algorithms::{Algorithm, GridBlock, GridMap, GridSize, Pathfinder, Point};
-use crate::display::setup::{config_setup, map_builder, MapBuilderMode};
+use crate::algorithms::comparison::compare;
+use crate::algorithms::events::EventRecorder;
+use crate::algorithms::heuristic::WeightedHeuristic;
+use crate::algorithms::jump_point_search;
+use crate::algorithms::{Algorithm, Connectivity, GridMap, Pathfinder, Point};
+use crate::display::animation::Playback;
+use crate::display::comparison::print_comparison;
+use crate::display::replanning::ReplanningDemo;
+use crate::display::result::{print_metrics, render_result};
+use crate::display::setup::{config_setup, connect_endpoints_prompt, map_setup, save_map_prompt};
 use crate::display::welcome::welcome;
 use crate::tui::confirm::Confirm;
 use crate::tui::refresh_display;
+use std::time::Duration;
 
 pub struct SetupConfig {
-    grid_size: GridSize,
-    algorithm: Algorithm,
+    algorithms: Vec<Algorithm>,
+    connectivity: Connectivity,
+    heuristic: WeightedHeuristic,
+    animation_delay: Duration,
 }
 
 impl SetupConfig {
-    pub fn new(grid_size: GridSize, algorithm: Algorithm) -> SetupConfig {
+    pub fn new(
+        algorithms: Vec<Algorithm>,
+        connectivity: Connectivity,
+        heuristic: WeightedHeuristic,
+        animation_delay: Duration,
+    ) -> SetupConfig {
         SetupConfig {
-            grid_size,
-            algorithm,
+            algorithms,
+            connectivity,
+            heuristic,
+            animation_delay,
         }
     }
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     welcome();
     let base_config = config_setup();
-    let mut grid_map = GridMap::new(base_config.grid_size);
-    grid_map = map_builder(MapBuilderMode::Obstacle, grid_map);
-    grid_map = map_builder(MapBuilderMode::Start, grid_map);
-    grid_map = map_builder(MapBuilderMode::End, grid_map);
+    let mut grid_map = map_setup(base_config.connectivity);
+    connect_endpoints_prompt(&mut grid_map);
+    if base_config.algorithms.contains(&Algorithm::JumpPointSearch)
+        && !jump_point_search::supports(&grid_map)
+    {
+        println!("Warning: {}", jump_point_search::FALLBACK_WARNING);
+    }
+    save_map_prompt(&grid_map);
     grid_map.render();
 
     let confirm_start = Confirm::new()
@@ -41,12 +71,88 @@ fn main() {
 
     refresh_display(grid_map.full_size);
 
-    let pathfinder = Pathfinder {
-        start: grid_map.start,
-        end: grid_map.end,
-        grid: grid_map,
-        algorithm: base_config.algorithm,
-    };
+    let (start, end) = (grid_map.start, grid_map.end);
+    if base_config.algorithms.len() == 2 && !base_config.animation_delay.is_zero() {
+        animate_side_by_side(&grid_map, &start, &end, &base_config);
+    }
+    if base_config.algorithms.len() > 1 {
+        grid_map.render();
+        let report = compare(
+            &grid_map,
+            &start,
+            &end,
+            &base_config.algorithms,
+            &base_config.heuristic,
+        );
+        print_comparison(&report);
+        return;
+    }
+
+    let pathfinder = Pathfinder::new(grid_map, start, end, base_config.algorithms[0])
+        .set_heuristic(base_config.heuristic);
+
+    if base_config.animation_delay.is_zero() {
+        let result = pathfinder.run();
+        render_result(
+            &pathfinder.grid,
+            &pathfinder.start,
+            &pathfinder.end,
+            &result,
+        );
+    } else {
+        let mut recorder = EventRecorder::new();
+        let result = pathfinder.run_observed(&mut recorder);
+        Playback::new(
+            &pathfinder.grid,
+            &pathfinder.start,
+            &pathfinder.end,
+            &recorder.events,
+            base_config.animation_delay,
+        )
+        .run();
+        print_metrics(&result);
+    }
+
+    if pathfinder.algorithm == Algorithm::DStarLite {
+        replanning_prompt(&pathfinder, base_config.animation_delay);
+    }
+}
+
+/**
+ * Offers to walk the path found by D Star Lite while obstacles are added and removed on the way
+ */
+fn replanning_prompt(pathfinder: &Pathfinder, delay: Duration) {
+    let confirm_demo = Confirm::new()
+        .set_message("Would you like to walk the path and change obstacles on the way?")
+        .ask();
+    if confirm_demo {
+        let heuristic = pathfinder.heuristic.set_weight(1.0);
+        ReplanningDemo::new(&pathfinder.grid, &heuristic, delay).run();
+    }
+}
+
+/**
+ * Animates both algorithms in lockstep on copies of the same grid
+ */
+fn animate_side_by_side(grid: &GridMap, start: &Point, end: &Point, config: &SetupConfig) {
+    let recorders: Vec<EventRecorder> = config
+        .algorithms
+        .iter()
+        .map(|algorithm| {
+            let mut recorder = EventRecorder::new();
+            Pathfinder::new(grid.clone(), *start, *end, *algorithm)
+                .set_heuristic(config.heuristic)
+                .run_observed(&mut recorder);
+            recorder
+        })
+        .collect();
 
-    pathfinder.run();
+    let searches = config
+        .algorithms
+        .iter()
+        .zip(&recorders)
+        .map(|(algorithm, recorder)| (algorithm.to_name().to_string(), recorder.events.as_slice()))
+        .collect();
+    Playback::side_by_side(grid, start, end, searches, config.animation_delay).run();
+    refresh_display(grid.full_size + 1);
 }
@@ -0,0 +1,295 @@
+/*!
+ * Plain text map files, a header of `key value` lines followed by one character per block
+ *
+ * # comments start with a hash
+ * width 8
+ * height 3
+ * connectivity 4-way
+ * diagonal_cost 1.4142135623730951
+ * seed 1234
+ * map
+ * S..#....
+ * ...#.gg.
+ * .......E
+ *
+ * The seed is only written for generated maps, the blocks are always saved so the map loads the same
+ * without it. Unknown header keys are ignored, see `GridBlock::to_char` for the block characters.
+ * Neither side may be longer than `MAX_DIMENSION` and the diagonal cost has to be from 1 to 2, a
+ * diagonal never costs less than a straight move or more than the two it replaces.
+ * Moving AI benchmark files have their own format and are read by `moving_ai`.
+ */
+pub mod moving_ai;
+
+use crate::algorithms::{Connectivity, GridBlock, GridMap, GridSize, Point};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/**
+ * The largest width and height a map file may have, the same limit as building a map by hand
+ */
+pub const MAX_DIMENSION: i32 = 100;
+
+#[derive(Debug)]
+pub enum MapFileError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Invalid(String),
+}
+
+impl MapFileError {
+    pub fn parse(line: usize, message: &str) -> MapFileError {
+        MapFileError::Parse {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn invalid(message: &str) -> MapFileError {
+        MapFileError::Invalid(message.to_string())
+    }
+}
+
+impl fmt::Display for MapFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapFileError::Io(error) => write!(f, "{}", error),
+            MapFileError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            MapFileError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for MapFileError {
+    fn from(error: io::Error) -> Self {
+        MapFileError::Io(error)
+    }
+}
+
+pub fn to_text(grid: &GridMap) -> String {
+    let mut text = String::new();
+    text.push_str("# path-finder map\n");
+    text.push_str(&format!("width {}\n", grid.size.width));
+    text.push_str(&format!("height {}\n", grid.size.height));
+    text.push_str(&format!("connectivity {}\n", grid.connectivity.to_name()));
+    text.push_str(&format!("diagonal_cost {}\n", grid.diagonal_cost));
+    if let Some(seed) = grid.seed {
+        text.push_str(&format!("seed {}\n", seed));
+    }
+    text.push_str("map\n");
+    for row in &grid.grid {
+        for element in row {
+            text.push(element.grid.to_char());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+pub fn from_text(text: &str) -> Result<GridMap, MapFileError> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let mut width = None;
+    let mut height = None;
+    let mut connectivity = Connectivity::FourWay;
+    let mut diagonal_cost = std::f64::consts::SQRT_2;
+    let mut seed = None;
+
+    loop {
+        let (number, line) = lines
+            .next()
+            .ok_or_else(|| MapFileError::invalid("missing `map` line before the blocks"))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == "map" {
+            break;
+        }
+
+        let (key, value) = line
+            .split_once(' ')
+            .ok_or_else(|| MapFileError::parse(number, "expected `key value`"))?;
+        let value = value.trim();
+        match key {
+            "width" => width = Some(parse_dimension(number, value)?),
+            "height" => height = Some(parse_dimension(number, value)?),
+            "connectivity" => {
+                connectivity = Connectivity::from_name(value)
+                    .ok_or_else(|| MapFileError::parse(number, "unknown connectivity"))?
+            }
+            "diagonal_cost" => {
+                diagonal_cost = match value.parse::<f64>() {
+                    Ok(cost) if (1.0..=2.0).contains(&cost) => cost,
+                    _ => {
+                        return Err(MapFileError::parse(
+                            number,
+                            "diagonal cost must be a number from 1 to 2",
+                        ))
+                    }
+                }
+            }
+            "seed" => {
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| MapFileError::parse(number, "seed must be a number"))?,
+                )
+            }
+            _ => {}
+        }
+    }
+
+    let width = width.ok_or_else(|| MapFileError::invalid("missing width"))?;
+    let height = height.ok_or_else(|| MapFileError::invalid("missing height"))?;
+    let mut grid = GridMap::new(GridSize::new(width, height));
+    grid.set_connectivity(connectivity);
+    grid.set_diagonal_cost(diagonal_cost);
+    grid.set_seed(seed);
+
+    let mut start = None;
+    let mut end = None;
+    for y in 0..height {
+        let (number, line) = lines
+            .next()
+            .ok_or_else(|| MapFileError::invalid("fewer rows than the height"))?;
+        let row: Vec<char> = line.trim_end().chars().collect();
+        if row.len() != width as usize {
+            return Err(MapFileError::parse(
+                number,
+                "row length does not match the width",
+            ));
+        }
+
+        for (x, c) in row.into_iter().enumerate() {
+            let point = Point::new(x as i32, y);
+            let block = GridBlock::from_char(c)
+                .ok_or_else(|| MapFileError::parse(number, &format!("unknown block `{}`", c)))?;
+            let marker = match block {
+                GridBlock::Start => &mut start,
+                GridBlock::End => &mut end,
+                _ => {
+                    grid.set_block(&point, &block);
+                    continue;
+                }
+            };
+            if marker.is_some() {
+                return Err(MapFileError::parse(
+                    number,
+                    &format!("more than one {} block", block.to_name()),
+                ));
+            }
+            *marker = Some(point);
+            grid.set_block(&point, &block);
+        }
+    }
+
+    if lines.any(|(_, line)| !line.trim().is_empty()) {
+        return Err(MapFileError::invalid("more rows than the height"));
+    }
+
+    if let Some(start) = start {
+        grid.set_start(&start);
+    }
+    if let Some(end) = end {
+        grid.set_end(&end);
+    }
+    Ok(grid)
+}
+
+fn parse_dimension(line: usize, value: &str) -> Result<i32, MapFileError> {
+    match value.parse::<i32>() {
+        Ok(dimension) if (1..=MAX_DIMENSION).contains(&dimension) => Ok(dimension),
+        _ => Err(MapFileError::parse(
+            line,
+            &format!("size must be a number from 1 to {}", MAX_DIMENSION),
+        )),
+    }
+}
+
+/**
+ * Maps larger than a map file may be, such as Moving AI benchmarks, are refused so every saved
+ * file loads again
+ */
+pub fn save(grid: &GridMap, path: impl AsRef<Path>) -> Result<(), MapFileError> {
+    if grid.size.width > MAX_DIMENSION || grid.size.height > MAX_DIMENSION {
+        return Err(MapFileError::invalid(&format!(
+            "maps larger than {0}x{0} can not be saved",
+            MAX_DIMENSION
+        )));
+    }
+    fs::write(path, to_text(grid))?;
+    Ok(())
+}
+
+/**
+ * Loads a map file, files with the `.map` extension are read as Moving AI benchmark maps
+ */
+pub fn load(path: impl AsRef<Path>) -> Result<GridMap, MapFileError> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|extension| extension == "map") {
+        return moving_ai::load_map(path);
+    }
+    from_text(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "\
+# path-finder map
+width 6
+height 3
+connectivity 8-way without corner cutting
+diagonal_cost 1.5
+seed 42
+map
+S.#gsw
+mhW...
+.....E
+";
+
+    #[test]
+    fn text_round_trips() {
+        let grid = from_text(TEXT).expect("map is valid");
+        assert_eq!(grid.size.width, 6);
+        assert_eq!(grid.size.height, 3);
+        assert_eq!(grid.connectivity, Connectivity::EightWayNoCornerCutting);
+        assert_eq!(grid.diagonal_cost, 1.5);
+        for cost in ["1", "2"] {
+            let text = format!("width 1\nheight 1\ndiagonal_cost {}\nmap\n.\n", cost);
+            assert!(from_text(&text).is_ok(), "{}", cost);
+        }
+        assert_eq!(grid.seed, Some(42));
+        assert_eq!(grid.start, Point::new(0, 0));
+        assert_eq!(grid.end, Point::new(5, 2));
+        assert_eq!(to_text(&grid), TEXT);
+    }
+
+    #[test]
+    fn rejects_sizes_past_the_limit() {
+        let text = format!(
+            "width {}\nheight 1\nmap\n{}\n",
+            MAX_DIMENSION + 1,
+            ".".repeat(MAX_DIMENSION as usize + 1)
+        );
+        assert!(matches!(
+            from_text(&text),
+            Err(MapFileError::Parse { line: 1, .. })
+        ));
+        assert!(from_text("width 0\nheight 1\nmap\n\n").is_err());
+    }
+
+    #[test]
+    fn rejects_diagonal_costs_outside_one_to_two() {
+        for cost in ["0", "-1", "0.5", "2.5", "NaN", "inf"] {
+            let text = format!("width 1\nheight 1\ndiagonal_cost {}\nmap\n.\n", cost);
+            assert!(
+                matches!(from_text(&text), Err(MapFileError::Parse { line: 3, .. })),
+                "{}",
+                cost
+            );
+        }
+    }
+}
@@ -0,0 +1,319 @@
+/*!
+ * Moving AI Lab benchmark maps and scenarios
+ * https://movingai.com/benchmarks/formats.html
+ *
+ * Maps are octile grids without corner cutting. Trees and out of bounds blocks are obstacles,
+ * swamps cost the same as open ground like the reference lengths expect and water stays water,
+ * passable at a higher cost.
+ */
+use super::MapFileError;
+use crate::algorithms::{Connectivity, GridBlock, GridMap, GridSize, Point, SearchResult};
+use std::fs;
+use std::path::Path;
+
+/**
+ * The difference allowed between a path cost and the reference length, the files round to 8 decimals
+ */
+const LENGTH_TOLERANCE: f64 = 1e-4;
+
+pub fn block_from_char(c: char) -> Option<GridBlock> {
+    match c {
+        '.' | 'G' | 'S' => Some(GridBlock::Empty),
+        '@' | 'O' | 'T' => Some(GridBlock::Obstacle),
+        'W' => Some(GridBlock::Water),
+        _ => None,
+    }
+}
+
+pub fn parse_map(text: &str) -> Result<GridMap, MapFileError> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let mut width = None;
+    let mut height = None;
+
+    loop {
+        let (number, line) = lines
+            .next()
+            .ok_or_else(|| MapFileError::invalid("missing `map` line before the blocks"))?;
+        let line = line.trim();
+        if line == "map" {
+            break;
+        }
+
+        let (key, value) = line
+            .split_once(' ')
+            .ok_or_else(|| MapFileError::parse(number, "expected `key value`"))?;
+        match key {
+            "type" if value.trim() != "octile" => {
+                return Err(MapFileError::parse(
+                    number,
+                    "only octile maps are supported",
+                ))
+            }
+            "width" => width = Some(parse_dimension(number, value)?),
+            "height" => height = Some(parse_dimension(number, value)?),
+            _ => {}
+        }
+    }
+
+    let width = width.ok_or_else(|| MapFileError::invalid("missing width"))?;
+    let height = height.ok_or_else(|| MapFileError::invalid("missing height"))?;
+    let mut grid = GridMap::new(GridSize::new(width, height));
+    grid.set_connectivity(Connectivity::EightWayNoCornerCutting);
+
+    for y in 0..height {
+        let (number, line) = lines
+            .next()
+            .ok_or_else(|| MapFileError::invalid("fewer rows than the height"))?;
+        let row: Vec<char> = line.trim_end().chars().collect();
+        if row.len() != width as usize {
+            return Err(MapFileError::parse(
+                number,
+                "row length does not match the width",
+            ));
+        }
+
+        for (x, c) in row.into_iter().enumerate() {
+            let block = block_from_char(c)
+                .ok_or_else(|| MapFileError::parse(number, &format!("unknown block `{}`", c)))?;
+            grid.set_block(&Point::new(x as i32, y), &block);
+        }
+    }
+
+    Ok(grid)
+}
+
+pub fn load_map(path: impl AsRef<Path>) -> Result<GridMap, MapFileError> {
+    parse_map(&fs::read_to_string(path)?)
+}
+
+/**
+ * A single search from a scenario file, the map is the path written in the file and the line is
+ * where the scenario was read from
+ */
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub line: usize,
+    pub bucket: i32,
+    pub map: String,
+    pub size: GridSize,
+    pub start: Point,
+    pub end: Point,
+    pub optimal_length: f64,
+}
+
+impl Scenario {
+    /**
+     * Whether the result found a path as short as the reference optimal length
+     */
+    pub fn is_optimal(&self, result: &SearchResult) -> bool {
+        result.found() && (result.total_cost - self.optimal_length).abs() < LENGTH_TOLERANCE
+    }
+
+    /**
+     * Checks the scenario was written for the map file and grid, with its start and end on
+     * passable blocks
+     */
+    pub fn check(&self, map_path: &Path, grid: &GridMap) -> Result<(), MapFileError> {
+        if Path::new(&self.map).file_name() != map_path.file_name() {
+            return Err(MapFileError::parse(
+                self.line,
+                &format!("the scenario is for the map `{}`", self.map),
+            ));
+        }
+        if self.size.width != grid.size.width || self.size.height != grid.size.height {
+            return Err(MapFileError::parse(
+                self.line,
+                &format!(
+                    "the scenario is for a {}x{} map",
+                    self.size.width, self.size.height
+                ),
+            ));
+        }
+        for (point, name) in [(&self.start, "start"), (&self.end, "goal")] {
+            if !grid.contains(point) {
+                return Err(MapFileError::parse(
+                    self.line,
+                    &format!("the {} {},{} is outside the map", name, point.x, point.y),
+                ));
+            }
+            if !grid.is_passable(point) {
+                return Err(MapFileError::parse(
+                    self.line,
+                    &format!("the {} {},{} is an obstacle", name, point.x, point.y),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Marks the scenario's start and end on the grid, the scenario has to be checked against it first
+     */
+    pub fn place_on(&self, grid: &mut GridMap) {
+        grid.set_block(&self.start, &GridBlock::Start);
+        grid.set_start(&self.start);
+        grid.set_block(&self.end, &GridBlock::End);
+        grid.set_end(&self.end);
+    }
+}
+
+pub fn parse_scenarios(text: &str) -> Result<Vec<Scenario>, MapFileError> {
+    let mut scenarios = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("version") {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 9 {
+            return Err(MapFileError::parse(
+                number,
+                "expected 9 tab separated fields",
+            ));
+        }
+        scenarios.push(Scenario {
+            line: number,
+            bucket: parse_number(number, fields[0])?,
+            map: fields[1].to_string(),
+            size: GridSize::new(
+                parse_number(number, fields[2])?,
+                parse_number(number, fields[3])?,
+            ),
+            start: Point::new(
+                parse_number(number, fields[4])?,
+                parse_number(number, fields[5])?,
+            ),
+            end: Point::new(
+                parse_number(number, fields[6])?,
+                parse_number(number, fields[7])?,
+            ),
+            optimal_length: parse_number(number, fields[8])?,
+        });
+    }
+    Ok(scenarios)
+}
+
+pub fn load_scenarios(path: impl AsRef<Path>) -> Result<Vec<Scenario>, MapFileError> {
+    parse_scenarios(&fs::read_to_string(path)?)
+}
+
+fn parse_dimension(line: usize, value: &str) -> Result<i32, MapFileError> {
+    match parse_number::<i32>(line, value)? {
+        dimension if dimension > 0 => Ok(dimension),
+        _ => Err(MapFileError::parse(line, "size must be a positive number")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(line: usize, value: &str) -> Result<T, MapFileError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| MapFileError::parse(line, &format!("`{}` is not a number", value.trim())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Algorithm, Pathfinder};
+
+    const MAP: &str = "type octile\nheight 3\nwidth 4\nmap\n.@..\nTSW.\n..G.\n";
+
+    fn scenarios(lines: &[&str]) -> String {
+        format!("version 1\n{}\n", lines.join("\n"))
+    }
+
+    #[test]
+    fn parse_map_reads_octile_blocks() {
+        let grid = parse_map(MAP).expect("map is valid");
+        assert_eq!(grid.size.width, 4);
+        assert_eq!(grid.size.height, 3);
+        assert_eq!(grid.connectivity, Connectivity::EightWayNoCornerCutting);
+        assert_eq!(grid.get_block(&Point::new(1, 0)), &GridBlock::Obstacle);
+        assert_eq!(grid.get_block(&Point::new(0, 1)), &GridBlock::Obstacle);
+        assert_eq!(grid.get_block(&Point::new(1, 1)), &GridBlock::Empty);
+        assert_eq!(grid.get_block(&Point::new(2, 1)), &GridBlock::Water);
+        assert_eq!(grid.get_block(&Point::new(2, 2)), &GridBlock::Empty);
+    }
+
+    #[test]
+    fn parse_map_rejects_bad_headers_and_rows() {
+        assert!(parse_map("type tile\nheight 1\nwidth 1\nmap\n.\n").is_err());
+        assert!(parse_map("type octile\nheight 0\nwidth 1\nmap\n").is_err());
+        assert!(parse_map("type octile\nheight -1\nwidth 1\nmap\n").is_err());
+        assert!(matches!(
+            parse_map("type octile\nheight 1\nwidth 2\nmap\n.\n"),
+            Err(MapFileError::Parse { line: 5, .. })
+        ));
+        assert!(matches!(
+            parse_map("type octile\nheight 1\nwidth 1\nmap\nx\n"),
+            Err(MapFileError::Parse { line: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn swamps_cost_the_same_as_open_ground() {
+        let mut grid = parse_map("type octile\nheight 3\nwidth 5\nmap\n@@@@@\n.SSS.\n@@@@@\n")
+            .expect("map is valid");
+        let scenario = &parse_scenarios(&scenarios(&["0\tsw.map\t5\t3\t0\t1\t4\t1\t4"]))
+            .expect("scenarios are valid")[0];
+        scenario.place_on(&mut grid);
+        let result = Pathfinder::new(grid.clone(), grid.start, grid.end, Algorithm::Dijkstra).run();
+        assert!(scenario.is_optimal(&result));
+    }
+
+    #[test]
+    fn parse_scenarios_reads_every_field() {
+        let text = scenarios(&["3\tmaps/test.map\t4\t3\t0\t0\t3\t2\t3.82842712"]);
+        let scenarios = parse_scenarios(&text).expect("scenarios are valid");
+        assert_eq!(scenarios.len(), 1);
+        let scenario = &scenarios[0];
+        assert_eq!(scenario.line, 2);
+        assert_eq!(scenario.bucket, 3);
+        assert_eq!(scenario.map, "maps/test.map");
+        assert_eq!(scenario.size.width, 4);
+        assert_eq!(scenario.size.height, 3);
+        assert_eq!(scenario.start, Point::new(0, 0));
+        assert_eq!(scenario.end, Point::new(3, 2));
+        assert_eq!(scenario.optimal_length, 3.82842712);
+    }
+
+    #[test]
+    fn parse_scenarios_rejects_malformed_lines() {
+        let missing_field = scenarios(&["0\ttest.map\t4\t3\t0\t0\t3\t2"]);
+        assert!(matches!(
+            parse_scenarios(&missing_field),
+            Err(MapFileError::Parse { line: 2, .. })
+        ));
+        let not_a_number = scenarios(&["0\ttest.map\t4\t3\t0\tx\t3\t2\t1"]);
+        assert!(matches!(
+            parse_scenarios(&not_a_number),
+            Err(MapFileError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn check_rejects_scenarios_for_another_map() {
+        let grid = parse_map(MAP).expect("map is valid");
+        let map_path = Path::new("benchmarks/test.map");
+        let check =
+            |line: &str| parse_scenarios(&scenarios(&[line])).unwrap()[0].check(map_path, &grid);
+
+        assert!(check("0\tmaps/test.map\t4\t3\t0\t0\t3\t2\t1").is_ok());
+        for line in [
+            "0\tmaps/other.map\t4\t3\t0\t0\t3\t2\t1",
+            "0\tmaps/test.map\t5\t3\t0\t0\t3\t2\t1",
+            "0\tmaps/test.map\t4\t3\t0\t0\t4\t2\t1",
+            "0\tmaps/test.map\t4\t3\t-1\t0\t3\t2\t1",
+            "0\tmaps/test.map\t4\t3\t1\t0\t3\t2\t1",
+            "0\tmaps/test.map\t4\t3\t0\t0\t0\t1\t1",
+        ] {
+            assert!(
+                matches!(check(line), Err(MapFileError::Parse { line: 2, .. })),
+                "{}",
+                line
+            );
+        }
+    }
+}
@@ -14,6 +14,12 @@ pub struct Confirm {
     manual_clear: bool,
 }
 
+impl Default for Confirm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Confirm {
     pub fn new() -> Self {
         Confirm {
@@ -41,12 +47,13 @@ impl Confirm {
             terminal::enable_raw_mode().expect("Failed to enable raw mode");
 
             let event = read().unwrap();
-            match event {
-                Event::Key(KeyEvent {
-                    code,
-                    kind: KeyEventKind::Press,
-                    ..
-                }) => match code {
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event
+            {
+                match code {
                     KeyCode::Char('q') => {
                         terminal::disable_raw_mode().unwrap();
                         std::process::exit(0);
@@ -66,8 +73,7 @@ impl Confirm {
                         return true;
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
             terminal::disable_raw_mode().expect("Failed to disable raw mode");
         }
@@ -1,6 +1,7 @@
 pub mod confirm;
 pub mod number_input;
 pub mod option_select;
+pub mod text_input;
 
 use crossterm::{cursor, terminal, ExecutableCommand};
 use std::io;
@@ -18,6 +18,12 @@ pub struct NumberInput {
     manual_clear: bool,
 }
 
+impl Default for NumberInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NumberInput {
     pub fn new() -> Self {
         NumberInput {
@@ -53,14 +59,9 @@ impl NumberInput {
     pub fn ask(&self) -> i32 {
         println!("{}", self.message);
 
-        if self.max.is_none() {
-            println!("Number must be at least {}", self.min);
-        } else {
-            println!(
-                "Number must be between {} and {}",
-                self.min,
-                self.max.unwrap()
-            );
+        match self.max {
+            Some(max) => println!("Number must be between {} and {}", self.min, max),
+            None => println!("Number must be at least {}", self.min),
         }
 
         let mut current_number_string = String::new();
@@ -70,20 +71,19 @@ impl NumberInput {
             terminal::enable_raw_mode().expect("Failed to enable raw mode");
 
             let event = read().unwrap();
-            match event {
-                Event::Key(KeyEvent {
-                    code,
-                    kind: KeyEventKind::Press,
-                    ..
-                }) => match code {
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event
+            {
+                match code {
                     KeyCode::Char('q') => {
                         terminal::disable_raw_mode().unwrap();
                         std::process::exit(0);
                     }
-                    KeyCode::Char(c) => {
-                        if c.is_numeric() {
-                            current_number_string.push(c);
-                        }
+                    KeyCode::Char(c) if c.is_numeric() => {
+                        current_number_string.push(c);
                     }
                     KeyCode::Backspace => {
                         current_number_string.pop();
@@ -109,8 +109,7 @@ impl NumberInput {
                         current_number_string = String::new();
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
 
             terminal::disable_raw_mode().expect("Failed to disable raw mode");
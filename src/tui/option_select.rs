@@ -18,6 +18,12 @@ pub struct OptionSelect {
     manual_clear: bool,
 }
 
+impl Default for OptionSelect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OptionSelect {
     pub fn new() -> Self {
         OptionSelect {
@@ -73,12 +79,13 @@ impl OptionSelect {
             terminal::enable_raw_mode().expect("Failed to enable raw mode");
 
             let event = read().unwrap();
-            match event {
-                Event::Key(KeyEvent {
-                    code,
-                    kind: KeyEventKind::Press,
-                    ..
-                }) => match code {
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event
+            {
+                match code {
                     KeyCode::Char('q') => {
                         terminal::disable_raw_mode().unwrap();
                         std::process::exit(0);
@@ -105,8 +112,7 @@ impl OptionSelect {
                         return self.options[current_option].to_string();
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
             terminal::disable_raw_mode().expect("Failed to disable raw mode");
             refresh_display(self.reset_size);
@@ -0,0 +1,96 @@
+use crossterm::{
+    event::{read, Event, KeyCode, KeyEvent, KeyEventKind},
+    terminal,
+};
+
+use crate::tui::refresh_display;
+
+/**
+ * Full size: the number of lines the element will take up
+ * Reset size: the number of lines the user input section will take up
+ *
+ * Every character can be typed, so Esc is used to quit instead of 'q'
+ */
+pub struct TextInput {
+    message: String,
+    allow_empty: bool,
+    pub full_size: i32,
+    reset_size: i32,
+    manual_clear: bool,
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        TextInput {
+            message: String::new(),
+            allow_empty: false,
+            full_size: 2,
+            reset_size: 1,
+            manual_clear: false,
+        }
+    }
+
+    pub fn set_message(mut self, message: &str) -> Self {
+        self.message = message.to_string();
+        self
+    }
+
+    pub fn allow_empty(mut self) -> Self {
+        self.allow_empty = true;
+        self
+    }
+
+    pub fn manual_clear(mut self) -> Self {
+        self.manual_clear = true;
+        self
+    }
+
+    pub fn ask(&self) -> String {
+        println!("{}", self.message);
+
+        let mut current_text = String::new();
+
+        loop {
+            println!("> {}", current_text);
+            terminal::enable_raw_mode().expect("Failed to enable raw mode");
+
+            let event = read().unwrap();
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event
+            {
+                match code {
+                    KeyCode::Esc => {
+                        terminal::disable_raw_mode().unwrap();
+                        std::process::exit(0);
+                    }
+                    KeyCode::Char(c) => {
+                        current_text.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        current_text.pop();
+                    }
+                    KeyCode::Enter if self.allow_empty || !current_text.trim().is_empty() => {
+                        terminal::disable_raw_mode().expect("Failed to disable raw mode");
+                        if !self.manual_clear {
+                            refresh_display(self.full_size);
+                        }
+                        return current_text.trim().to_string();
+                    }
+                    _ => {}
+                }
+            }
+
+            terminal::disable_raw_mode().expect("Failed to disable raw mode");
+            refresh_display(self.reset_size);
+        }
+    }
+}